@@ -0,0 +1,190 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use oasis_std::types::{H256, U256};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_eth::Address;
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Counter counts the allocations made through the system allocator,
+/// so every case reports how many allocations a single call makes
+struct Counter;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counter {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counter = Counter;
+
+/// report_allocations prints the number of allocations and the bytes
+/// allocated by a single call of `f`
+fn report_allocations<R, F: FnOnce() -> R>(name: &str, f: F) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let allocated = ALLOCATED.load(Ordering::Relaxed);
+    let result = f();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    let allocated = ALLOCATED.load(Ordering::Relaxed) - allocated;
+    drop(result);
+
+    println!(
+        "{:<32} {:>10} allocations {:>12} bytes",
+        name, allocations, allocated
+    );
+}
+
+/// Transfer has the arguments of an erc20 transfer
+#[derive(Serialize, Deserialize)]
+struct Transfer {
+    to: Address,
+    amount: U256,
+}
+
+/// Tree is a recursive value whose encoding nests a dynamic tuple and
+/// a sequence for every level of depth
+#[derive(Serialize, Deserialize)]
+struct Tree {
+    name: String,
+    children: Vec<Tree>,
+}
+
+/// Blob is a single `bytes` value
+#[derive(Serialize, Deserialize)]
+struct Blob {
+    #[serde(with = "serde_eth::hex_bytes")]
+    data: Vec<u8>,
+}
+
+/// Composed has the shape of the value in `test_composed_struct`
+type Composed = Vec<Vec<(String, (H256, [u32; 4]))>>;
+
+fn transfer() -> Transfer {
+    Transfer {
+        to: Address::from([0x11; 20]),
+        amount: U256::from(1_000_000_000_000_000_000u64),
+    }
+}
+
+fn tree(depth: usize, branches: usize) -> Tree {
+    Tree {
+        name: format!("depth {}", depth),
+        children: if depth == 0 {
+            Vec::new()
+        } else {
+            (0..branches).map(|_| tree(depth - 1, branches)).collect()
+        },
+    }
+}
+
+fn composed(width: usize) -> Composed {
+    (0..width)
+        .map(|i| {
+            (0..width)
+                .map(|j| {
+                    let name = format!("item {} {}", i, j);
+                    (name, (H256::from([i as u8; 32]), [j as u32; 4]))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn blob(len: usize) -> Blob {
+    Blob {
+        data: (0..len).map(|i| i as u8).collect(),
+    }
+}
+
+/// bench_case benchmarks encoding and decoding of `value`, with the
+/// length of its encoding as the throughput
+fn bench_case<T: Serialize + DeserializeOwned>(c: &mut Criterion, group: &str, id: &str, value: T) {
+    let encoded = serde_eth::to_string(&value).unwrap();
+    report_allocations(&format!("to_vec/{}/{}", group, id), || {
+        serde_eth::to_vec(&value).unwrap()
+    });
+    report_allocations(&format!("from_str/{}/{}", group, id), || {
+        serde_eth::from_str::<T>(&encoded).unwrap()
+    });
+
+    let mut encode = c.benchmark_group(format!("to_vec/{}", group));
+    encode.throughput(Throughput::Bytes(encoded.len() as u64));
+    encode.bench_with_input(BenchmarkId::from_parameter(id), &value, |b, value| {
+        b.iter(|| serde_eth::to_vec(value).unwrap())
+    });
+    encode.finish();
+
+    let mut decode = c.benchmark_group(format!("from_str/{}", group));
+    decode.throughput(Throughput::Bytes(encoded.len() as u64));
+    decode.bench_with_input(BenchmarkId::from_parameter(id), &encoded, |b, encoded| {
+        b.iter(|| serde_eth::from_str::<T>(encoded).unwrap())
+    });
+    decode.finish();
+}
+
+fn bench_small(c: &mut Criterion) {
+    bench_case(c, "small", "transfer", transfer());
+    bench_case(c, "small", "u64", 7u64);
+    bench_case(c, "small", "string", "hello".to_string());
+}
+
+fn bench_arrays(c: &mut Criterion) {
+    for len in [100, 10_000] {
+        let values: Vec<u64> = (0..len).collect();
+        bench_case(c, "u64_array", &len.to_string(), values);
+    }
+
+    for len in [100, 10_000] {
+        let values: Vec<String> = (0..len).map(|i| format!("value {}", i)).collect();
+        bench_case(c, "string_array", &len.to_string(), values);
+    }
+}
+
+fn bench_nested(c: &mut Criterion) {
+    // every level of depth is two scopes for the deserializer, so this
+    // stays within the default max_depth
+    for depth in [4, 16, 32] {
+        bench_case(c, "depth", &depth.to_string(), tree(depth, 1));
+    }
+
+    for depth in [2, 4, 8] {
+        bench_case(c, "tree", &depth.to_string(), tree(depth, 2));
+    }
+
+    for width in [1, 4, 16, 64] {
+        bench_case(c, "composed", &width.to_string(), composed(width));
+    }
+}
+
+fn bench_blobs(c: &mut Criterion) {
+    for len in [1 << 10, 1 << 16, 1 << 20] {
+        bench_case(c, "blob", &len.to_string(), blob(len));
+    }
+}
+
+criterion_group!(
+    benches,
+    bench_small,
+    bench_arrays,
+    bench_nested,
+    bench_blobs
+);
+criterion_main!(benches);
@@ -0,0 +1,195 @@
+//! Generates a corpus of random values of random eth abi types together
+//! with their encoding by ethabi, which serde-eth is tested against.
+//!
+//! Every case is the type string, the value as JSON and the hex encoding
+//! of the value as the only parameter of a function call, which is how
+//! `serde_eth::to_string` encodes a single value. Integers are decimal
+//! strings, and bytes and addresses `0x` prefixed hex strings.
+
+use ethabi::{
+    ethereum_types::{H160, U256},
+    ParamType, Token,
+};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde_json::{json, Value};
+
+use std::{env, fs, path::PathBuf};
+
+const CASES: usize = 500;
+const SEED: u64 = 0x05e4_dee7;
+const MAX_DEPTH: usize = 3;
+const MAX_LEN: usize = 3;
+
+const CHARS: &[char] = &['a', 'z', '0', ' ', '_', 'é', 'ß', '中', '€', '😀'];
+
+fn gen_type<R: Rng>(rng: &mut R, depth: usize) -> ParamType {
+    // compound types are more likely closer to the root
+    let leaves = 11;
+    let kind = if depth < MAX_DEPTH && rng.gen_range(0..MAX_DEPTH + 1) > depth {
+        rng.gen_range(leaves..leaves + 3)
+    } else {
+        rng.gen_range(0..leaves)
+    };
+
+    match kind {
+        0 => ParamType::Uint(*[8, 16, 32, 64].choose(rng).unwrap()),
+        1 | 2 => ParamType::Uint(256),
+        3 => ParamType::Int(*[8, 16, 32, 64].choose(rng).unwrap()),
+        4 => ParamType::Bool,
+        5 | 6 => ParamType::String,
+        7 => ParamType::Bytes,
+        8 | 9 => ParamType::FixedBytes(rng.gen_range(1..=32)),
+        10 => ParamType::Address,
+        11 => ParamType::Array(Box::new(gen_type(rng, depth + 1))),
+        12 => ParamType::FixedArray(
+            Box::new(gen_type(rng, depth + 1)),
+            rng.gen_range(1..=MAX_LEN),
+        ),
+        _ => ParamType::Tuple(
+            (0..rng.gen_range(1..=MAX_LEN))
+                .map(|_| gen_type(rng, depth + 1))
+                .collect(),
+        ),
+    }
+}
+
+fn type_string(kind: &ParamType) -> String {
+    match kind {
+        ParamType::Uint(bits) => format!("uint{}", bits),
+        ParamType::Int(bits) => format!("int{}", bits),
+        ParamType::Bool => "bool".to_string(),
+        ParamType::String => "string".to_string(),
+        ParamType::Bytes => "bytes".to_string(),
+        ParamType::FixedBytes(len) => format!("bytes{}", len),
+        ParamType::Address => "address".to_string(),
+        ParamType::Array(inner) => format!("{}[]", type_string(inner)),
+        ParamType::FixedArray(inner, len) => format!("{}[{}]", type_string(inner), len),
+        ParamType::Tuple(inner) => format!(
+            "({})",
+            inner.iter().map(type_string).collect::<Vec<_>>().join(",")
+        ),
+    }
+}
+
+/// gen_uint returns an unsigned integer of `bits` bits, biased towards
+/// edge cases and multiples of 32 that look like offsets
+fn gen_uint<R: Rng>(rng: &mut R, bits: usize) -> U256 {
+    let max = if bits == 256 {
+        U256::MAX
+    } else {
+        (U256::one() << bits) - 1
+    };
+
+    match rng.gen_range(0..6) {
+        0 => U256::zero(),
+        1 => max,
+        2 => U256::from(rng.gen_range(1u64..8) * 32) & max,
+        3 => U256::from(rng.gen::<u8>()),
+        _ => {
+            let mut bytes = [0u8; 32];
+            rng.fill(&mut bytes[..]);
+            U256::from_big_endian(&bytes) & max
+        }
+    }
+}
+
+fn gen_int<R: Rng>(rng: &mut R, bits: usize) -> i64 {
+    let max = if bits == 64 {
+        i64::MAX
+    } else {
+        (1i64 << (bits - 1)) - 1
+    };
+    let min = -max - 1;
+
+    match rng.gen_range(0..6) {
+        0 => 0,
+        1 => max,
+        2 => min,
+        3 => -1,
+        _ => rng.gen_range(min..=max),
+    }
+}
+
+fn gen_bytes<R: Rng>(rng: &mut R, len: usize) -> Vec<u8> {
+    (0..len).map(|_| rng.gen()).collect()
+}
+
+fn gen_value<R: Rng>(rng: &mut R, kind: &ParamType) -> (Token, Value) {
+    match kind {
+        ParamType::Uint(bits) => {
+            let value = gen_uint(rng, *bits);
+            (Token::Uint(value), json!(value.to_string()))
+        }
+        ParamType::Int(bits) => {
+            let value = gen_int(rng, *bits);
+            let token = if value < 0 {
+                !U256::from((-(value as i128) - 1) as u64)
+            } else {
+                U256::from(value as u64)
+            };
+            (Token::Int(token), json!(value.to_string()))
+        }
+        ParamType::Bool => {
+            let value = rng.gen();
+            (Token::Bool(value), json!(value))
+        }
+        ParamType::String => {
+            let len = *[0, 1, 5, 31, 32, 33, 70].choose(rng).unwrap();
+            let value: String = (0..len).map(|_| *CHARS.choose(rng).unwrap()).collect();
+            (Token::String(value.clone()), json!(value))
+        }
+        ParamType::Bytes => {
+            let len = *[0, 1, 20, 32, 33, 64, 100].choose(rng).unwrap();
+            let value = gen_bytes(rng, len);
+            let hex = format!("0x{}", hex::encode(&value));
+            (Token::Bytes(value), json!(hex))
+        }
+        ParamType::FixedBytes(len) => {
+            let value = gen_bytes(rng, *len);
+            let hex = format!("0x{}", hex::encode(&value));
+            (Token::FixedBytes(value), json!(hex))
+        }
+        ParamType::Address => {
+            let value = gen_bytes(rng, 20);
+            let hex = format!("0x{}", hex::encode(&value));
+            (Token::Address(H160::from_slice(&value)), json!(hex))
+        }
+        ParamType::Array(inner) => {
+            let len = rng.gen_range(0..=MAX_LEN);
+            let (tokens, values) = (0..len).map(|_| gen_value(rng, inner)).unzip();
+            (Token::Array(tokens), Value::Array(values))
+        }
+        ParamType::FixedArray(inner, len) => {
+            let (tokens, values) = (0..*len).map(|_| gen_value(rng, inner)).unzip();
+            (Token::FixedArray(tokens), Value::Array(values))
+        }
+        ParamType::Tuple(inner) => {
+            let (tokens, values) = inner.iter().map(|kind| gen_value(rng, kind)).unzip();
+            (Token::Tuple(tokens), Value::Array(values))
+        }
+    }
+}
+
+fn main() {
+    let mut rng = ChaCha8Rng::seed_from_u64(SEED);
+    let mut cases = Vec::with_capacity(CASES);
+
+    for _ in 0..CASES {
+        let kind = gen_type(&mut rng, 0);
+        let (token, value) = gen_value(&mut rng, &kind);
+        assert!(token.type_check(&kind));
+
+        cases.push(json!({
+            "type": type_string(&kind),
+            "value": value,
+            "encoded": hex::encode(ethabi::encode(&[token])),
+        }));
+    }
+
+    let path = env::args().nth(1).map(PathBuf::from).unwrap_or_else(|| {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../testdata/corpus.json")
+    });
+    let corpus = serde_json::to_string_pretty(&cases).unwrap();
+    fs::write(&path, corpus + "\n").unwrap();
+}
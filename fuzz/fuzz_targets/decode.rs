@@ -0,0 +1,56 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use oasis_std::types::{H256, U256};
+use serde::Deserialize;
+use serde_eth::de::DeserializerOptions;
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+struct Simple {
+    value: u64,
+    name: String,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+struct Nested {
+    simple: Simple,
+    values: Vec<String>,
+    hash: H256,
+}
+
+macro_rules! decode_all {
+    ($input:expr, $options:expr, $($ty:ty),* $(,)*) => {
+        $(
+            let _ = serde_eth::from_str_with_options::<$ty>($input, $options.clone());
+        )*
+    };
+}
+
+fn decode(input: &str, options: DeserializerOptions) {
+    decode_all!(
+        input,
+        options,
+        u64,
+        bool,
+        String,
+        H256,
+        U256,
+        Option<u64>,
+        Option<String>,
+        Vec<Vec<u64>>,
+        Vec<Vec<String>>,
+        Simple,
+        Nested,
+        Vec<Nested>,
+        (String, (H256, [u32; 4])),
+    );
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        decode(input, DeserializerOptions::default());
+        decode(input, DeserializerOptions::default().strict(true));
+    }
+});
@@ -0,0 +1,114 @@
+//! Serialize a value as its eth abi encoding. Human readable formats store
+//! the encoding as a `0x` prefixed hex string, other formats store the raw
+//! encoded bytes. To be used with `#[serde(with = ...)]`:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Request {
+//!     #[serde(with = "serde_eth::abi_encoded")]
+//!     args: (u64, String),
+//! }
+//! ```
+
+use serde::{de, ser};
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use super::{de::from_str, ser::to_string};
+
+pub fn serialize<T: ser::Serialize, S: ser::Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let encoded = to_string(value).map_err(ser::Error::custom)?;
+
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&format!("0x{}", encoded))
+    } else {
+        let bytes = hex::decode(&encoded).map_err(ser::Error::custom)?;
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+pub fn deserialize<'de, T: de::DeserializeOwned, D: de::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    let encoded = if deserializer.is_human_readable() {
+        deserializer.deserialize_str(EncodedVisitor)?
+    } else {
+        deserializer.deserialize_byte_buf(EncodedVisitor)?
+    };
+
+    from_str(&encoded).map_err(de::Error::custom)
+}
+
+/// EncodedVisitor returns the hex encoding without the `0x` prefix,
+/// which is what the eth abi deserializer expects
+struct EncodedVisitor;
+
+impl<'de> de::Visitor<'de> for EncodedVisitor {
+    type Value = String;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a 0x prefixed hex string with an eth abi encoding")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<String, E> {
+        if !value.starts_with("0x") {
+            return Err(E::custom("abi encoded value is missing 0x prefix"));
+        }
+
+        Ok(value[2..].to_string())
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<String, E> {
+        Ok(hex::encode(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use alloc::string::{String, ToString};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Request {
+        method: String,
+        #[serde(with = "super")]
+        args: (u64, String),
+    }
+
+    #[test]
+    fn test_json() {
+        let request = Request {
+            method: "transfer".to_string(),
+            args: (1, "1".to_string()),
+        };
+
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            "{\"method\":\"transfer\",\"args\":\"0x\
+             0000000000000000000000000000000000000000000000000000000000000020\
+             0000000000000000000000000000000000000000000000000000000000000001\
+             0000000000000000000000000000000000000000000000000000000000000040\
+             0000000000000000000000000000000000000000000000000000000000000001\
+             3100000000000000000000000000000000000000000000000000000000000000\"}"
+        );
+        assert_eq!(serde_json::from_str::<Request>(&json).unwrap(), request);
+    }
+
+    #[test]
+    fn test_json_error() {
+        let tests = &[
+            r#"{"method":"transfer","args":"0000"}"#,
+            r#"{"method":"transfer","args":"0x00"}"#,
+        ];
+
+        for json in tests {
+            assert!(serde_json::from_str::<Request>(json).is_err());
+        }
+    }
+}
@@ -0,0 +1,657 @@
+//! Eth abi types described by their type strings, and values of them, for
+//! tools that only learn the types of an encoding at runtime, like the
+//! `serde-eth` command line tool.
+//!
+//! ```ignore
+//! let signature = Signature::parse("transfer(address,uint256)")?;
+//! let values = serde_eth::abi_type::decode_params(&signature.inputs, &s)?;
+//! assert_eq!(serde_eth::abi_type::encode_params(&values)?, s);
+//! ```
+
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt, str::FromStr};
+
+use serde::{
+    de::{self, Deserialize},
+    ser::{self, Serialize, SerializeTuple},
+};
+
+use super::{
+    de::{from_reader_seed, DeserializerOptions},
+    error::{Error, ErrorKind, Result},
+    eth, hex_bytes,
+    io::Cursor,
+    ser::to_string_params,
+    tokens::{self, Elements},
+    Address, FixedBytes,
+};
+
+/// AbiType is an eth abi type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiType {
+    Uint(usize),
+    Int(usize),
+    Bool,
+    String,
+    Bytes,
+    FixedBytes(usize),
+    Address,
+    Array(Box<AbiType>),
+    FixedArray(Box<AbiType>, usize),
+    Tuple(Vec<AbiType>),
+}
+
+fn type_error(s: &str) -> Error {
+    Error::new(ErrorKind::Custom, &format!("invalid type {}", s))
+}
+
+impl AbiType {
+    /// parse parses a type string, e.g. `(uint256,string)[]`. `uint` and
+    /// `int` are aliases of `uint256` and `int256`
+    pub fn parse(s: &str) -> Result<AbiType> {
+        let compact: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        match AbiType::parse_prefix(&compact)? {
+            (kind, "") => Ok(kind),
+            _ => Err(type_error(s)),
+        }
+    }
+
+    /// parse_list parses a comma separated list of types, e.g. the
+    /// parameters of a function
+    pub fn parse_list(s: &str) -> Result<Vec<AbiType>> {
+        match AbiType::parse(&format!("({})", s)) {
+            Ok(AbiType::Tuple(kinds)) => Ok(kinds),
+            _ => Err(type_error(s)),
+        }
+    }
+
+    fn parse_prefix(s: &str) -> Result<(AbiType, &str)> {
+        let (mut kind, mut rest) = if let Some(rest) = s.strip_prefix('(') {
+            let (kinds, rest) = AbiType::parse_tuple(rest)?;
+            (AbiType::Tuple(kinds), rest)
+        } else {
+            let end = s.find(['[', ',', ')']).unwrap_or(s.len());
+            (AbiType::parse_base(&s[..end])?, &s[end..])
+        };
+
+        while let Some(r) = rest.strip_prefix('[') {
+            let end = r.find(']').ok_or_else(|| type_error(s))?;
+            kind = match &r[..end] {
+                "" => AbiType::Array(Box::new(kind)),
+                len => {
+                    let len = len.parse().map_err(|_| type_error(s))?;
+                    AbiType::FixedArray(Box::new(kind), len)
+                }
+            };
+            rest = &r[end + 1..];
+        }
+
+        Ok((kind, rest))
+    }
+
+    /// parse_tuple parses the types of a tuple up to the closing parenthesis
+    fn parse_tuple(s: &str) -> Result<(Vec<AbiType>, &str)> {
+        let mut kinds = Vec::new();
+        if let Some(rest) = s.strip_prefix(')') {
+            return Ok((kinds, rest));
+        }
+
+        let mut rest = s;
+        loop {
+            let (kind, r) = AbiType::parse_prefix(rest)?;
+            kinds.push(kind);
+            match r.as_bytes().first() {
+                Some(b',') => rest = &r[1..],
+                Some(b')') => return Ok((kinds, &r[1..])),
+                _ => return Err(type_error(s)),
+            }
+        }
+    }
+
+    fn parse_base(s: &str) -> Result<AbiType> {
+        let size = |n: &str, max: usize, step: usize| match n.parse::<usize>() {
+            Ok(n) if n > 0 && n <= max && n.is_multiple_of(step) => Ok(n),
+            _ => Err(type_error(s)),
+        };
+
+        Ok(match s {
+            "bool" => AbiType::Bool,
+            "string" => AbiType::String,
+            "bytes" => AbiType::Bytes,
+            "address" => AbiType::Address,
+            "uint" => AbiType::Uint(256),
+            "int" => AbiType::Int(256),
+            _ => {
+                if let Some(bits) = s.strip_prefix("uint") {
+                    AbiType::Uint(size(bits, 256, 8)?)
+                } else if let Some(bits) = s.strip_prefix("int") {
+                    AbiType::Int(size(bits, 256, 8)?)
+                } else if let Some(len) = s.strip_prefix("bytes") {
+                    AbiType::FixedBytes(size(len, 32, 1)?)
+                } else {
+                    return Err(type_error(s));
+                }
+            }
+        })
+    }
+
+    /// is_dynamic returns whether values of the type are encoded in the
+    /// tail of the tuple that contains them
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            AbiType::String | AbiType::Bytes | AbiType::Array(_) => true,
+            AbiType::FixedArray(kind, _) => kind.is_dynamic(),
+            AbiType::Tuple(kinds) => kinds.iter().any(AbiType::is_dynamic),
+            _ => false,
+        }
+    }
+
+    /// parse_value parses a value of a type that is not an array or a
+    /// tuple. Integers are decimal or `0x` prefixed hex, and bytes and
+    /// addresses `0x` prefixed hex
+    pub fn parse_value(&self, s: &str) -> Result<AbiValue> {
+        let invalid = || {
+            Error::new(
+                ErrorKind::Custom,
+                &format!("invalid value {} for {}", s, self),
+            )
+        };
+
+        Ok(match self {
+            AbiType::Uint(bits) => AbiValue::Uint(tokens::uint_word(parse_word(s)?, *bits)?),
+            AbiType::Int(bits) => {
+                let (negative, digits) = match s.strip_prefix('-') {
+                    Some(digits) => (true, digits),
+                    None => (false, s),
+                };
+                let mut word = parse_word(digits)?.0;
+                if negative && word.iter().any(|b| *b != 0) {
+                    negate(&mut word);
+                    if word[0] & 0x80 == 0 {
+                        return Err(invalid());
+                    }
+                } else if word[0] & 0x80 != 0 {
+                    return Err(invalid());
+                }
+                AbiValue::Int(tokens::int_word(FixedBytes(word), *bits)?)
+            }
+            AbiType::Bool => match s {
+                "true" => AbiValue::Bool(true),
+                "false" => AbiValue::Bool(false),
+                _ => return Err(invalid()),
+            },
+            AbiType::String => AbiValue::String(s.to_string()),
+            AbiType::Bytes => AbiValue::Bytes(hex_bytes::decode(s).map_err(|_| invalid())?),
+            AbiType::FixedBytes(len) => {
+                let bytes = hex_bytes::decode(s).map_err(|_| invalid())?;
+                if bytes.len() != *len {
+                    return Err(invalid());
+                }
+                AbiValue::FixedBytes(bytes)
+            }
+            AbiType::Address => AbiValue::Address(Address::from_str(s)?),
+            AbiType::Array(_) | AbiType::FixedArray(_, _) | AbiType::Tuple(_) => {
+                return Err(Error::new(
+                    ErrorKind::Custom,
+                    &format!("cannot parse a value of {} from a string", self),
+                ))
+            }
+        })
+    }
+}
+
+impl FromStr for AbiType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<AbiType> {
+        AbiType::parse(s)
+    }
+}
+
+/// write_list writes items separated by commas
+fn write_list<T: fmt::Display>(f: &mut fmt::Formatter, items: &[T]) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            f.write_str(",")?;
+        }
+        fmt::Display::fmt(item, f)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for AbiType {
+    /// fmt writes the canonical type string, as used in signatures
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AbiType::Uint(bits) => write!(f, "uint{}", bits),
+            AbiType::Int(bits) => write!(f, "int{}", bits),
+            AbiType::Bool => f.write_str("bool"),
+            AbiType::String => f.write_str("string"),
+            AbiType::Bytes => f.write_str("bytes"),
+            AbiType::FixedBytes(len) => write!(f, "bytes{}", len),
+            AbiType::Address => f.write_str("address"),
+            AbiType::Array(kind) => write!(f, "{}[]", kind),
+            AbiType::FixedArray(kind, len) => write!(f, "{}[{}]", kind, len),
+            AbiType::Tuple(kinds) => {
+                f.write_str("(")?;
+                write_list(f, kinds)?;
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+/// Signature is the name of a function together with the types of its
+/// parameters, e.g. `transfer(address,uint256)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    pub name: String,
+    pub inputs: Vec<AbiType>,
+}
+
+impl Signature {
+    /// parse parses a signature. The name may be empty, in which case
+    /// the signature only describes a list of parameters, which has to
+    /// be the end of the signature
+    pub fn parse(s: &str) -> Result<Signature> {
+        let s = s.trim();
+        let invalid = || Error::new(ErrorKind::Custom, &format!("invalid signature {}", s));
+        let start = s.find('(').ok_or_else(invalid)?;
+        let list = s[start + 1..].strip_suffix(')').ok_or_else(invalid)?;
+        Ok(Signature {
+            name: s[..start].to_string(),
+            inputs: AbiType::parse_list(list)?,
+        })
+    }
+
+    /// selector returns the first 4 bytes of the keccak256 hash of the
+    /// canonical signature, which prefix the parameters in calldata
+    pub fn selector(&self) -> [u8; 4] {
+        let hash = eth::keccak256(self.to_string().as_bytes());
+        let mut selector = [0u8; 4];
+        selector.copy_from_slice(&hash[..4]);
+        selector
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}(", self.name)?;
+        write_list(f, &self.inputs)?;
+        f.write_str(")")
+    }
+}
+
+/// AbiValue is a value of an `AbiType`. Integers are kept as their 32
+/// bytes big endian word, in two's complement if they are signed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiValue {
+    Uint([u8; 32]),
+    Int([u8; 32]),
+    Bool(bool),
+    String(String),
+    Bytes(Vec<u8>),
+    FixedBytes(Vec<u8>),
+    Address(Address),
+    Array(Vec<AbiValue>),
+    FixedArray(Vec<AbiValue>),
+    Tuple(Vec<AbiValue>),
+}
+
+impl Serialize for AbiValue {
+    fn serialize<S: ser::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        match self {
+            AbiValue::Uint(word) | AbiValue::Int(word) => tokens::serialize_word(*word, serializer),
+            AbiValue::Bool(value) => serializer.serialize_bool(*value),
+            AbiValue::String(value) => serializer.serialize_str(value),
+            AbiValue::Bytes(bytes) => serializer.serialize_bytes(bytes),
+            AbiValue::FixedBytes(bytes) => tokens::serialize_fixed_bytes(bytes, serializer),
+            AbiValue::Address(address) => address.serialize(serializer),
+            AbiValue::Array(values) => serializer.collect_seq(values),
+            AbiValue::FixedArray(values) | AbiValue::Tuple(values) => {
+                let mut tuple = serializer.serialize_tuple(values.len())?;
+                for value in values {
+                    tuple.serialize_element(value)?;
+                }
+                tuple.end()
+            }
+        }
+    }
+}
+
+impl fmt::Display for AbiValue {
+    /// fmt writes integers in decimal, bytes and addresses in hex and
+    /// strings quoted
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AbiValue::Uint(word) => f.write_str(&to_decimal(*word)),
+            AbiValue::Int(word) if word[0] & 0x80 != 0 => {
+                let mut word = *word;
+                negate(&mut word);
+                write!(f, "-{}", to_decimal(word))
+            }
+            AbiValue::Int(word) => f.write_str(&to_decimal(*word)),
+            AbiValue::Bool(value) => write!(f, "{}", value),
+            AbiValue::String(value) => write!(f, "{:?}", value),
+            AbiValue::Bytes(bytes) | AbiValue::FixedBytes(bytes) => {
+                f.write_str(&hex_bytes::encode(bytes))
+            }
+            AbiValue::Address(address) => write!(f, "{}", address),
+            AbiValue::Array(values) | AbiValue::FixedArray(values) => {
+                f.write_str("[")?;
+                write_list(f, values)?;
+                f.write_str("]")
+            }
+            AbiValue::Tuple(values) => {
+                f.write_str("(")?;
+                write_list(f, values)?;
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+/// parse_word parses an unsigned integer of up to 256 bits, in decimal
+/// or `0x` prefixed hex
+fn parse_word(s: &str) -> Result<FixedBytes<32>> {
+    let invalid = || Error::new(ErrorKind::Custom, &format!("invalid integer {}", s));
+    let mut word = [0u8; 32];
+
+    if let Some(digits) = s.strip_prefix("0x") {
+        let bytes = hex_bytes::decode(&format!("0x{:0>64}", digits)).map_err(|_| invalid())?;
+        if bytes.len() != 32 {
+            return Err(Error::new(
+                ErrorKind::IntegerOverflow,
+                "integer does not fit in 256 bits",
+            ));
+        }
+        word.copy_from_slice(&bytes);
+        return Ok(FixedBytes(word));
+    }
+
+    if s.is_empty() {
+        return Err(invalid());
+    }
+    for c in s.chars() {
+        let mut carry = c.to_digit(10).ok_or_else(invalid)?;
+        for byte in word.iter_mut().rev() {
+            let value = u32::from(*byte) * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return Err(Error::new(
+                ErrorKind::IntegerOverflow,
+                "integer does not fit in 256 bits",
+            ));
+        }
+    }
+    Ok(FixedBytes(word))
+}
+
+/// to_decimal writes an unsigned word in decimal
+fn to_decimal(mut word: [u8; 32]) -> String {
+    let mut digits = Vec::new();
+    loop {
+        let mut remainder = 0u32;
+        for byte in word.iter_mut() {
+            let value = (remainder << 8) | u32::from(*byte);
+            *byte = (value / 10) as u8;
+            remainder = value % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+        if word.iter().all(|b| *b == 0) {
+            break;
+        }
+    }
+    digits.iter().rev().map(|d| char::from(*d)).collect()
+}
+
+/// negate replaces a word with its two's complement
+fn negate(word: &mut [u8; 32]) {
+    let mut carry = 1u16;
+    for byte in word.iter_mut().rev() {
+        let value = u16::from(!*byte) + carry;
+        *byte = value as u8;
+        carry = value >> 8;
+    }
+}
+
+/// TypeSeed deserializes a value of the given type
+#[derive(Clone, Copy)]
+pub struct TypeSeed<'a>(pub &'a AbiType);
+
+impl<'de, 'a> de::DeserializeSeed<'de> for TypeSeed<'a> {
+    type Value = AbiValue;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> core::result::Result<AbiValue, D::Error> {
+        Ok(match self.0 {
+            AbiType::Uint(bits) => AbiValue::Uint(tokens::uint_word(
+                FixedBytes::deserialize(deserializer)?,
+                *bits,
+            )?),
+            AbiType::Int(bits) => AbiValue::Int(tokens::int_word(
+                FixedBytes::deserialize(deserializer)?,
+                *bits,
+            )?),
+            AbiType::Bool => AbiValue::Bool(bool::deserialize(deserializer)?),
+            AbiType::String => AbiValue::String(String::deserialize(deserializer)?),
+            AbiType::Bytes => AbiValue::Bytes(hex_bytes::deserialize(deserializer)?),
+            AbiType::FixedBytes(len) => {
+                let word = tokens::fixed_bytes_word(FixedBytes::deserialize(deserializer)?, *len)?;
+                AbiValue::FixedBytes(word[..*len].to_vec())
+            }
+            AbiType::Address => AbiValue::Address(Address::deserialize(deserializer)?),
+            AbiType::Array(kind) => AbiValue::Array(deserializer.deserialize_seq(Elements {
+                seeds: Vec::new(),
+                element: Some(TypeSeed(kind)),
+            })?),
+            AbiType::FixedArray(kind, len) => AbiValue::FixedArray(tokens::deserialize_tuple(
+                deserializer,
+                *len,
+                self.0.is_dynamic(),
+                Elements {
+                    seeds: vec![TypeSeed(kind); *len],
+                    element: None,
+                },
+            )?),
+            AbiType::Tuple(kinds) => AbiValue::Tuple(tokens::deserialize_tuple(
+                deserializer,
+                kinds.len(),
+                self.0.is_dynamic(),
+                Elements {
+                    seeds: kinds.iter().map(TypeSeed).collect(),
+                    element: None,
+                },
+            )?),
+        })
+    }
+}
+
+/// encode_params encodes values as the list of parameters of a function call
+pub fn encode_params(values: &[AbiValue]) -> Result<String> {
+    to_string_params(&AbiValue::Tuple(values.to_vec()))
+}
+
+/// decode_params decodes the list of parameters of a function call into
+/// values of the given types
+pub fn decode_params(types: &[AbiType], s: &str) -> Result<Vec<AbiValue>> {
+    let kind = AbiType::Tuple(types.to_vec());
+    let options = DeserializerOptions::new().params(true);
+    match from_reader_seed(Cursor::new(s), TypeSeed(&kind), options)? {
+        AbiValue::Tuple(values) => Ok(values),
+        _ => unreachable!("a tuple type decodes into a tuple value"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{decode_params, encode_params, AbiType, AbiValue, Signature, TypeSeed};
+    use crate::{
+        de::{from_reader_seed, DeserializerOptions},
+        from_str_seed,
+        io::Cursor,
+        to_string, to_string_params, Address,
+    };
+    use alloc::{
+        boxed::Box,
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    #[test]
+    fn test_parse_type() {
+        let kind = AbiType::parse("(uint, bytes3[2])[]").unwrap();
+        assert_eq!(
+            kind,
+            AbiType::Array(Box::new(AbiType::Tuple(vec![
+                AbiType::Uint(256),
+                AbiType::FixedArray(Box::new(AbiType::FixedBytes(3)), 2),
+            ])))
+        );
+        assert_eq!(kind.to_string(), "(uint256,bytes3[2])[]");
+        assert!(kind.is_dynamic());
+        assert!(!AbiType::parse("(address,int8)[3]").unwrap().is_dynamic());
+        assert_eq!(AbiType::parse("()").unwrap(), AbiType::Tuple(vec![]));
+        assert_eq!(AbiType::parse_list("").unwrap(), vec![]);
+
+        for invalid in [
+            "uint7", "uint264", "bytes0", "bytes33", "foo", "(uint8", "uint8[", "uint8)",
+        ] {
+            assert!(AbiType::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_signature() {
+        let signature = Signature::parse("transfer(address, uint)").unwrap();
+        assert_eq!(signature.to_string(), "transfer(address,uint256)");
+        assert_eq!(signature.selector(), [0xa9, 0x05, 0x9c, 0xbb]);
+
+        let signature = Signature::parse("(string,bool)").unwrap();
+        assert_eq!(signature.name, "");
+        assert_eq!(signature.inputs, vec![AbiType::String, AbiType::Bool]);
+        assert!(Signature::parse("transfer").is_err());
+
+        // the closing parenthesis is missing, or something follows it
+        assert!(Signature::parse("f(uint256").is_err());
+        assert!(Signature::parse("f(uint256)x").is_err());
+        assert!(Signature::parse("f(uint256)(bool)").is_err());
+        assert_eq!(Signature::parse("f()").unwrap().inputs, vec![]);
+    }
+
+    #[test]
+    fn test_parse_value() {
+        let parse = |ty: &str, s: &str| AbiType::parse(ty).unwrap().parse_value(s);
+        let display = |ty: &str, s: &str| parse(ty, s).unwrap().to_string();
+
+        assert_eq!(display("uint8", "255"), "255");
+        assert_eq!(display("uint16", "0x100"), "256");
+        assert_eq!(
+            display("uint256", &"9".repeat(77)),
+            "9".repeat(77),
+            "fits in 256 bits"
+        );
+        assert!(parse("uint256", &"9".repeat(78)).is_err());
+        assert!(parse("uint8", "256").is_err());
+        assert!(parse("uint8", "-1").is_err());
+
+        assert_eq!(display("int8", "-128"), "-128");
+        assert_eq!(display("int8", "127"), "127");
+        assert_eq!(display("int256", "-0"), "0");
+        assert!(parse("int8", "128").is_err());
+        assert!(parse("int8", "-129").is_err());
+        assert_eq!(
+            parse("int16", "-2").unwrap(),
+            AbiValue::Int({
+                let mut word = [0xff; 32];
+                word[31] = 0xfe;
+                word
+            })
+        );
+
+        assert_eq!(display("bool", "true"), "true");
+        assert!(parse("bool", "1").is_err());
+        assert_eq!(display("string", "a\"b"), "\"a\\\"b\"");
+        assert_eq!(display("bytes", "0x0102"), "0x0102");
+        assert!(parse("bytes2", "0x01").is_err());
+        assert_eq!(
+            display("address", "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"),
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+        );
+        assert!(parse("uint8[]", "[1]").is_err());
+    }
+
+    #[test]
+    fn test_corpus() {
+        let corpus: serde_json::Value =
+            serde_json::from_str(include_str!("../testdata/corpus.json")).unwrap();
+
+        for case in corpus.as_array().unwrap() {
+            let ty = case["type"].as_str().unwrap();
+            let encoded = case["encoded"].as_str().unwrap();
+            let kind = AbiType::parse(ty).unwrap();
+
+            let value = from_str_seed(encoded, TypeSeed(&kind))
+                .unwrap_or_else(|err| panic!("decoding {}: {}", ty, err));
+            assert_eq!(to_string(&value).unwrap(), encoded, "encoding {}", ty);
+        }
+    }
+
+    #[test]
+    fn test_params() {
+        let value = (
+            Address::from([0x11; 20]),
+            7u64,
+            -2i32,
+            vec![("abc".to_string(), 1u8), (String::new(), 2u8)],
+        );
+        let types = AbiType::parse_list("address,uint64,int32,(string,uint8)[]").unwrap();
+
+        let s = to_string_params(&value).unwrap();
+        let values = decode_params(&types, &s).unwrap();
+        assert_eq!(
+            values
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "0x1111111111111111111111111111111111111111",
+                "7",
+                "-2",
+                "[(\"abc\",1),(\"\",2)]"
+            ]
+        );
+        assert_eq!(encode_params(&values).unwrap(), s);
+
+        assert!(decode_params(&types[..1], &s[..63]).is_err());
+    }
+
+    #[test]
+    fn test_decode_static_tuples_like_offsets() {
+        // the first field of every tuple looks like an offset, but the
+        // type tells that the tuples are static, so nothing is guessed
+        let value: Vec<(u64, u64)> = (0..200).map(|i| (0x2000, i)).collect();
+        let s = to_string_params(&(value,)).unwrap();
+        let types = AbiType::parse_list("(uint64,uint64)[]").unwrap();
+
+        let values = decode_params(&types, &s).unwrap();
+        assert_eq!(encode_params(&values).unwrap(), s);
+
+        let kind = AbiType::Tuple(types);
+        let options = DeserializerOptions::new().params(true).max_retries(0);
+        let decoded = from_reader_seed(Cursor::new(&s), TypeSeed(&kind), options).unwrap();
+        assert_eq!(decoded, AbiValue::Tuple(values));
+    }
+}
@@ -0,0 +1,311 @@
+use serde::{de, ser};
+
+use alloc::string::String;
+use core::{fmt, str::FromStr};
+
+use super::{
+    error::{Error, ErrorKind, Result},
+    eth,
+};
+
+/// Address is a 20 byte ethereum account address.
+///
+/// When serialized with a human readable format (e.g. JSON) the
+/// address is written as an EIP-55 mixed-case checksum string. Otherwise
+/// it is serialized with the same shape as oasis-std `H160`, so the
+/// eth abi serializer encodes it as an `address`.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Address([u8; 20]);
+
+impl Address {
+    /// zero returns the zero address
+    pub fn zero() -> Self {
+        Address([0u8; 20])
+    }
+
+    /// from_slice creates an address from a slice that must be
+    /// exactly 20 bytes long
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 20 {
+            return Err(Error::new(
+                ErrorKind::InvalidLength,
+                "invalid byte array size for address",
+            ));
+        }
+
+        let mut address = [0u8; 20];
+        address.copy_from_slice(bytes);
+        Ok(Address(address))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+
+    /// to_checksum returns the EIP-55 representation of the address,
+    /// including the `0x` prefix
+    pub fn to_checksum(&self) -> String {
+        to_checksum(&self.0)
+    }
+
+    /// from_checksum parses an address that must be correctly checksummed
+    /// according to EIP-55. Unlike `from_str`, all lowercase or all uppercase
+    /// inputs are only accepted if they happen to be the checksummed form
+    pub fn from_checksum(s: &str) -> Result<Self> {
+        let address = parse(s)?;
+        if strip_prefix(s) != &to_checksum(&address.0)[2..] {
+            return Err(Error::new(
+                ErrorKind::InvalidChecksum,
+                "invalid address checksum",
+            ));
+        }
+        Ok(address)
+    }
+}
+
+fn strip_prefix(s: &str) -> &str {
+    if s.starts_with("0x") || s.starts_with("0X") {
+        &s[2..]
+    } else {
+        s
+    }
+}
+
+fn parse(s: &str) -> Result<Address> {
+    let s = strip_prefix(s);
+    if s.len() != 40 {
+        return Err(Error::new(
+            ErrorKind::InvalidLength,
+            "invalid address length",
+        ));
+    }
+
+    let decoded = hex::decode(s).map_err(Error::hex_parsing)?;
+    Address::from_slice(&decoded)
+}
+
+/// to_checksum encodes the address bytes as an EIP-55 checksummed
+/// hex string with the `0x` prefix
+pub fn to_checksum(address: &[u8; 20]) -> String {
+    let lower = hex::encode(address);
+    let hash = eth::keccak256(lower.as_bytes());
+
+    let mut result = String::with_capacity(42);
+    result.push_str("0x");
+    for (i, c) in lower.chars().enumerate() {
+        // the nibble of the hash at the same position as the character
+        // decides whether a letter is uppercased
+        let nibble = if i % 2 == 0 {
+            hash[i / 2] >> 4
+        } else {
+            hash[i / 2] & 0x0f
+        };
+
+        if nibble >= 8 {
+            result.push(c.to_ascii_uppercase());
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+/// is_valid_checksum returns true if the string is a hex encoded address
+/// whose letter casing matches the EIP-55 checksum
+pub fn is_valid_checksum(s: &str) -> bool {
+    Address::from_checksum(s).is_ok()
+}
+
+impl FromStr for Address {
+    type Err = Error;
+
+    /// from_str parses a hex encoded address with or without the `0x` prefix.
+    /// Mixed-case input is validated against its EIP-55 checksum, while all
+    /// lowercase or all uppercase input is accepted without a checksum
+    fn from_str(s: &str) -> Result<Self> {
+        let address = parse(s)?;
+        let digits = strip_prefix(s);
+        let is_lower = !digits.chars().any(|c| c.is_ascii_uppercase());
+        let is_upper = !digits.chars().any(|c| c.is_ascii_lowercase());
+
+        if is_lower || is_upper {
+            Ok(address)
+        } else {
+            Address::from_checksum(s)
+        }
+    }
+}
+
+impl From<[u8; 20]> for Address {
+    fn from(bytes: [u8; 20]) -> Self {
+        Address(bytes)
+    }
+}
+
+impl From<Address> for [u8; 20] {
+    fn from(address: Address) -> Self {
+        address.0
+    }
+}
+
+#[cfg(feature = "oasis-std")]
+impl From<oasis_std::types::Address> for Address {
+    fn from(address: oasis_std::types::Address) -> Self {
+        Address(address.0)
+    }
+}
+
+#[cfg(feature = "oasis-std")]
+impl From<Address> for oasis_std::types::Address {
+    fn from(address: Address) -> Self {
+        oasis_std::types::Address::from(address.0)
+    }
+}
+
+impl AsRef<[u8]> for Address {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_checksum())
+    }
+}
+
+impl fmt::Debug for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Address({})", self.to_checksum())
+    }
+}
+
+impl ser::Serialize for Address {
+    fn serialize<S: ser::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_checksum())
+        } else {
+            // same shape as the H160 types so that the eth serializer
+            // picks the fixed size encoding for it
+            serializer.serialize_newtype_struct("H160", &self.0)
+        }
+    }
+}
+
+struct AddressVisitor;
+
+impl<'de> de::Visitor<'de> for AddressVisitor {
+    type Value = Address;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an ethereum address")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> core::result::Result<Address, E> {
+        value.parse().map_err(de::Error::custom)
+    }
+
+    fn visit_newtype_struct<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> core::result::Result<Address, D::Error> {
+        let bytes: [u8; 20] = de::Deserialize::deserialize(deserializer)?;
+        Ok(Address(bytes))
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> core::result::Result<Address, A::Error> {
+        let bytes: [u8; 20] = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        Ok(Address(bytes))
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Address {
+    fn deserialize<D: de::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(AddressVisitor)
+        } else {
+            deserializer.deserialize_newtype_struct("H160", AddressVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{is_valid_checksum, Address};
+    use crate::ErrorKind;
+
+    // test vectors from EIP-55
+    const CHECKSUMMED: &[&str] = &[
+        "0x52908400098527886E0F7030069857D2E4169EE7",
+        "0x8617E340B3D01FA5F11F306F4090FD50E238070D",
+        "0xde709f2102306220921060314715629080e2fb77",
+        "0x27b1fdb04752bbc536007a920d24acb045561c26",
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn test_checksum_roundtrip() {
+        for s in CHECKSUMMED {
+            let address: Address = s.to_lowercase().parse().unwrap();
+            assert_eq!(address.to_checksum(), *s);
+            assert_eq!(Address::from_checksum(s).unwrap(), address);
+            assert!(is_valid_checksum(s));
+        }
+    }
+
+    #[test]
+    fn test_checksum_error() {
+        // flip the case of one letter
+        let invalid = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD";
+        assert_eq!(
+            invalid.parse::<Address>().unwrap_err().kind(),
+            ErrorKind::InvalidChecksum
+        );
+        assert_eq!(
+            Address::from_checksum(invalid).unwrap_err().kind(),
+            ErrorKind::InvalidChecksum
+        );
+        assert!(!is_valid_checksum(invalid));
+
+        // all lowercase is accepted when parsing but it is not checksummed
+        let lower = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+        assert!(lower.parse::<Address>().is_ok());
+        assert!(!is_valid_checksum(lower));
+        assert_eq!(
+            Address::from_checksum(lower).unwrap_err().kind(),
+            ErrorKind::InvalidChecksum
+        );
+
+        assert!("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA"
+            .parse::<Address>()
+            .is_err());
+        assert!("0xzaAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+            .parse::<Address>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_human_readable() {
+        let address: Address = CHECKSUMMED[4].parse().unwrap();
+        let json = serde_json::to_string(&address).unwrap();
+        assert_eq!(json, format!("\"{}\"", CHECKSUMMED[4]));
+
+        let decoded: Address = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, address);
+    }
+}
@@ -0,0 +1,236 @@
+//! Conversions between alloy's dynamic values and the eth abi encoding, so
+//! values encoded by this crate can be decoded into a `DynSolValue` tree
+//! and dynamic values can be decoded into typed values.
+//!
+//! ```ignore
+//! let s = serde_eth::to_string_params(&(7u64, "abc"))?;
+//! let ty = DynSolType::parse("(uint64,string)")?;
+//! let value = serde_eth::alloy_value::decode_params(&ty, &s)?;
+//! let typed: (u64, String) =
+//!     serde_eth::from_str_params(&serde_eth::alloy_value::encode_params(&value)?)?;
+//! ```
+
+use alloy_dyn_abi::{DynSolType, DynSolValue};
+use alloy_primitives::{Function, I256, U256};
+use serde::{
+    de::{self, Deserialize},
+    ser::{self, Serialize, SerializeTuple},
+};
+
+use std::io::Cursor;
+
+use super::{
+    de::{from_reader_seed, DeserializerOptions},
+    error::Result,
+    hex_bytes,
+    ser::to_string_params,
+    tokens::{self, Elements},
+    Address, FixedBytes,
+};
+
+/// ValueRef serializes a dynamic value as the value of its eth abi type
+pub struct ValueRef<'a>(pub &'a DynSolValue);
+
+impl<'a> Serialize for ValueRef<'a> {
+    #[allow(unreachable_patterns)]
+    fn serialize<S: ser::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        match self.0 {
+            DynSolValue::Bool(value) => serializer.serialize_bool(*value),
+            DynSolValue::Int(value, _) => tokens::serialize_word(value.to_be_bytes(), serializer),
+            DynSolValue::Uint(value, _) => tokens::serialize_word(value.to_be_bytes(), serializer),
+            DynSolValue::FixedBytes(word, len) => {
+                tokens::serialize_fixed_bytes(word.get(..*len).unwrap_or(&[]), serializer)
+            }
+            DynSolValue::Address(address) => {
+                Address::from(address.into_array()).serialize(serializer)
+            }
+            DynSolValue::Function(function) => {
+                tokens::serialize_fixed_bytes(&function[..], serializer)
+            }
+            DynSolValue::Bytes(bytes) => serializer.serialize_bytes(bytes),
+            DynSolValue::String(value) => serializer.serialize_str(value),
+            DynSolValue::Array(values) => serializer.collect_seq(values.iter().map(ValueRef)),
+            DynSolValue::FixedArray(values) | DynSolValue::Tuple(values) => {
+                let mut tuple = serializer.serialize_tuple(values.len())?;
+                for value in values {
+                    tuple.serialize_element(&ValueRef(value))?;
+                }
+                tuple.end()
+            }
+            _ => Err(ser::Error::custom("unsupported dynamic value")),
+        }
+    }
+}
+
+/// TypeSeed deserializes a dynamic value of the given type
+#[derive(Clone, Copy)]
+pub struct TypeSeed<'a>(pub &'a DynSolType);
+
+impl<'de, 'a> de::DeserializeSeed<'de> for TypeSeed<'a> {
+    type Value = DynSolValue;
+
+    #[allow(unreachable_patterns)]
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> core::result::Result<DynSolValue, D::Error> {
+        Ok(match self.0 {
+            DynSolType::Bool => DynSolValue::Bool(bool::deserialize(deserializer)?),
+            DynSolType::Int(bits) => {
+                let word = tokens::int_word(FixedBytes::deserialize(deserializer)?, *bits)?;
+                DynSolValue::Int(I256::from_be_bytes(word), *bits)
+            }
+            DynSolType::Uint(bits) => {
+                let word = tokens::uint_word(FixedBytes::deserialize(deserializer)?, *bits)?;
+                DynSolValue::Uint(U256::from_be_bytes(word), *bits)
+            }
+            DynSolType::FixedBytes(len) => {
+                let word = tokens::fixed_bytes_word(FixedBytes::deserialize(deserializer)?, *len)?;
+                DynSolValue::FixedBytes(word.into(), *len)
+            }
+            DynSolType::Address => {
+                let address = Address::deserialize(deserializer)?;
+                DynSolValue::Address(<[u8; 20]>::from(address).into())
+            }
+            DynSolType::Function => {
+                let word = tokens::fixed_bytes_word(FixedBytes::deserialize(deserializer)?, 24)?;
+                DynSolValue::Function(Function::from_slice(&word[..24]))
+            }
+            DynSolType::Bytes => DynSolValue::Bytes(hex_bytes::deserialize(deserializer)?),
+            DynSolType::String => DynSolValue::String(String::deserialize(deserializer)?),
+            DynSolType::Array(ty) => {
+                DynSolValue::Array(deserializer.deserialize_seq(Elements {
+                    seeds: Vec::new(),
+                    element: Some(TypeSeed(ty)),
+                })?)
+            }
+            DynSolType::FixedArray(ty, len) => DynSolValue::FixedArray(tokens::deserialize_tuple(
+                deserializer,
+                *len,
+                self.0.is_dynamic(),
+                Elements {
+                    seeds: vec![TypeSeed(ty); *len],
+                    element: None,
+                },
+            )?),
+            DynSolType::Tuple(types) => DynSolValue::Tuple(tokens::deserialize_tuple(
+                deserializer,
+                types.len(),
+                self.0.is_dynamic(),
+                Elements {
+                    seeds: types.iter().map(TypeSeed).collect(),
+                    element: None,
+                },
+            )?),
+            _ => return Err(de::Error::custom("unsupported dynamic type")),
+        })
+    }
+}
+
+/// encode_params encodes a value as the list of parameters of a function
+/// call, like `DynSolValue::abi_encode_params` but as a hex string
+pub fn encode_params(value: &DynSolValue) -> Result<String> {
+    to_string_params(&ValueRef(value))
+}
+
+/// decode_params decodes the list of parameters of a function call into
+/// a value of the given type, like `DynSolType::abi_decode_params` does
+/// with the binary encoding
+pub fn decode_params(ty: &DynSolType, s: &str) -> Result<DynSolValue> {
+    let options = DeserializerOptions::new().params(true);
+    from_reader_seed(Cursor::new(s), TypeSeed(ty), options)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{decode_params, encode_params, TypeSeed, ValueRef};
+    use crate::{
+        de::{from_reader_seed, DeserializerOptions},
+        from_str_params, from_str_seed, to_string, to_string_params, Address,
+    };
+    use alloy_dyn_abi::{DynSolType, DynSolValue};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_corpus() {
+        let corpus: serde_json::Value =
+            serde_json::from_str(include_str!("../testdata/corpus.json")).unwrap();
+
+        for case in corpus.as_array().unwrap() {
+            let ty = case["type"].as_str().unwrap();
+            let encoded = case["encoded"].as_str().unwrap();
+            let kind = DynSolType::parse(ty).unwrap();
+
+            let value = from_str_seed(encoded, TypeSeed(&kind))
+                .unwrap_or_else(|err| panic!("decoding {}: {}", ty, err));
+            let expected = kind.abi_decode(&hex::decode(encoded).unwrap()).unwrap();
+            assert_eq!(value, expected, "decoding {}", ty);
+            assert_eq!(
+                to_string(&ValueRef(&value)).unwrap(),
+                encoded,
+                "encoding {}",
+                ty
+            );
+        }
+    }
+
+    #[test]
+    fn test_params() {
+        let value = (
+            Address::from([0x11; 20]),
+            7u64,
+            -2i32,
+            vec![("abc".to_string(), 1u8), (String::new(), 2u8)],
+            ["x".to_string(), "yz".to_string()],
+        );
+        let ty = DynSolType::parse("(address,uint64,int32,(string,uint8)[],string[2])").unwrap();
+
+        let s = to_string_params(&value).unwrap();
+        let decoded = decode_params(&ty, &s).unwrap();
+        let expected = ty.abi_decode_params(&hex::decode(&s).unwrap()).unwrap();
+        assert_eq!(decoded, expected);
+
+        assert_eq!(encode_params(&decoded).unwrap(), s);
+        assert_eq!(hex::encode(decoded.abi_encode_params()), s);
+        assert_eq!(
+            from_str_params::<(Address, u64, i32, Vec<(String, u8)>, [String; 2])>(&s).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn test_decode_error() {
+        let word = |s: &str| format!("{:0>64}", s);
+        let parse = |s: &str| DynSolType::parse(s).unwrap();
+
+        assert!(decode_params(&parse("uint8"), &word("ff")).is_ok());
+        assert!(decode_params(&parse("uint8"), &word("100")).is_err());
+        assert!(decode_params(&parse("int8"), &word("80")).is_err());
+        assert!(decode_params(&parse("int8"), &format!("{:f>64}", "80")).is_ok());
+        assert!(decode_params(&parse("bytes1"), &word("01")).is_err());
+
+        let value = DynSolValue::FixedBytes(Default::default(), 33);
+        assert!(encode_params(&value).is_err());
+    }
+
+    #[test]
+    fn test_decode_static_tuples_like_offsets() {
+        // the first field of every tuple looks like an offset, but the
+        // type tells that the tuples are static, so nothing is guessed
+        let value: Vec<(u64, u64)> = (0..200).map(|i| (0x2000, i)).collect();
+        let s = to_string_params(&(value,)).unwrap();
+        let ty = DynSolType::parse("((uint64,uint64)[])").unwrap();
+
+        let decoded = decode_params(&ty, &s).unwrap();
+        let expected = ty.abi_decode_params(&hex::decode(&s).unwrap()).unwrap();
+        assert_eq!(decoded, expected);
+
+        let options = DeserializerOptions::new().params(true).max_retries(0);
+        let decoded = from_reader_seed(Cursor::new(&s), TypeSeed(&ty), options).unwrap();
+        assert_eq!(decoded, expected);
+    }
+}
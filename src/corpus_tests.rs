@@ -0,0 +1,290 @@
+//! Differential tests against the corpus in `testdata/corpus.json`, which
+//! is generated from ethabi by the crate in `corpus/`. Every case has an
+//! eth abi type string, a value and the expected encoding of the value.
+
+use crate::{
+    de::{from_reader_seed, from_str_seed, DeserializerOptions},
+    hex_bytes,
+    io::Cursor,
+    ser::to_string,
+    Address, FixedBytes,
+};
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+use oasis_std::types::U256;
+use serde::{
+    de::{self, Deserialize},
+    ser::{self, Serialize, SerializeTuple},
+};
+
+/// Kind is an eth abi type parsed from its type string
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Kind {
+    Uint(usize),
+    Int(usize),
+    Bool,
+    String,
+    Bytes,
+    FixedBytes(usize),
+    Address,
+    Array(Box<Kind>),
+    FixedArray(Box<Kind>, usize),
+    Tuple(Vec<Kind>),
+}
+
+impl Kind {
+    pub(crate) fn parse(s: &str) -> Kind {
+        let (kind, rest) = Kind::parse_prefix(s);
+        assert!(rest.is_empty(), "unexpected {} in type {}", rest, s);
+        kind
+    }
+
+    fn parse_prefix(s: &str) -> (Kind, &str) {
+        let (mut kind, mut rest) = if let Some(mut rest) = s.strip_prefix('(') {
+            let mut kinds = Vec::new();
+            loop {
+                let (kind, r) = Kind::parse_prefix(rest);
+                kinds.push(kind);
+                match r.as_bytes()[0] {
+                    b',' => rest = &r[1..],
+                    b')' => break (Kind::Tuple(kinds), &r[1..]),
+                    _ => panic!("unexpected {} in type {}", r, s),
+                }
+            }
+        } else {
+            let end = s.find(['[', ',', ')']).unwrap_or(s.len());
+            (Kind::parse_base(&s[..end]), &s[end..])
+        };
+
+        while let Some(r) = rest.strip_prefix('[') {
+            let end = r.find(']').unwrap();
+            kind = match &r[..end] {
+                "" => Kind::Array(Box::new(kind)),
+                len => Kind::FixedArray(Box::new(kind), len.parse().unwrap()),
+            };
+            rest = &r[end + 1..];
+        }
+
+        (kind, rest)
+    }
+
+    fn parse_base(s: &str) -> Kind {
+        match s {
+            "bool" => Kind::Bool,
+            "string" => Kind::String,
+            "bytes" => Kind::Bytes,
+            "address" => Kind::Address,
+            _ => {
+                if let Some(bits) = s.strip_prefix("uint") {
+                    Kind::Uint(bits.parse().unwrap())
+                } else if let Some(bits) = s.strip_prefix("int") {
+                    Kind::Int(bits.parse().unwrap())
+                } else if let Some(len) = s.strip_prefix("bytes") {
+                    Kind::FixedBytes(len.parse().unwrap())
+                } else {
+                    panic!("unsupported type {}", s)
+                }
+            }
+        }
+    }
+}
+
+/// Value is a value of any of the eth abi types in the corpus
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Uint(u64),
+    Uint256(U256),
+    Int(i64),
+    Bool(bool),
+    String(String),
+    Bytes(Vec<u8>),
+    FixedBytes(Vec<u8>),
+    Address(Address),
+    Seq(Vec<Value>),
+    Tuple(Vec<Value>),
+}
+
+fn hex_value(value: &serde_json::Value) -> Vec<u8> {
+    hex_bytes::decode(value.as_str().unwrap()).unwrap()
+}
+
+impl Value {
+    fn from_json(kind: &Kind, value: &serde_json::Value) -> Value {
+        let items = |value: &serde_json::Value| value.as_array().unwrap().clone();
+
+        match kind {
+            Kind::Uint(256) => Value::Uint256(U256::from_dec_str(value.as_str().unwrap()).unwrap()),
+            Kind::Uint(_) => Value::Uint(value.as_str().unwrap().parse().unwrap()),
+            Kind::Int(_) => Value::Int(value.as_str().unwrap().parse().unwrap()),
+            Kind::Bool => Value::Bool(value.as_bool().unwrap()),
+            Kind::String => Value::String(value.as_str().unwrap().to_string()),
+            Kind::Bytes => Value::Bytes(hex_value(value)),
+            Kind::FixedBytes(_) => Value::FixedBytes(hex_value(value)),
+            Kind::Address => Value::Address(Address::from_slice(&hex_value(value)).unwrap()),
+            Kind::Array(kind) => Value::Seq(
+                items(value)
+                    .iter()
+                    .map(|value| Value::from_json(kind, value))
+                    .collect(),
+            ),
+            Kind::FixedArray(kind, _) => Value::Tuple(
+                items(value)
+                    .iter()
+                    .map(|value| Value::from_json(kind, value))
+                    .collect(),
+            ),
+            Kind::Tuple(kinds) => Value::Tuple(
+                kinds
+                    .iter()
+                    .zip(items(value).iter())
+                    .map(|(kind, value)| Value::from_json(kind, value))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Uint(value) => serializer.serialize_u64(*value),
+            Value::Uint256(value) => value.serialize(serializer),
+            Value::Int(value) => serializer.serialize_i64(*value),
+            Value::Bool(value) => serializer.serialize_bool(*value),
+            Value::String(value) => serializer.serialize_str(value),
+            Value::Bytes(value) => serializer.serialize_bytes(value),
+            Value::FixedBytes(value) => {
+                let mut word = [0u8; 32];
+                word[..value.len()].copy_from_slice(value);
+                FixedBytes(word).serialize(serializer)
+            }
+            Value::Address(value) => value.serialize(serializer),
+            Value::Seq(values) => serializer.collect_seq(values),
+            Value::Tuple(values) => {
+                let mut tuple = serializer.serialize_tuple(values.len())?;
+                for value in values {
+                    tuple.serialize_element(value)?;
+                }
+                tuple.end()
+            }
+        }
+    }
+}
+
+/// KindSeed deserializes a Value of the given kind
+#[derive(Clone, Copy)]
+struct KindSeed<'a>(&'a Kind);
+
+impl<'de, 'a> de::DeserializeSeed<'de> for KindSeed<'a> {
+    type Value = Value;
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Ok(match self.0 {
+            Kind::Uint(8) => Value::Uint(u8::deserialize(deserializer)?.into()),
+            Kind::Uint(16) => Value::Uint(u16::deserialize(deserializer)?.into()),
+            Kind::Uint(32) => Value::Uint(u32::deserialize(deserializer)?.into()),
+            Kind::Uint(64) => Value::Uint(u64::deserialize(deserializer)?),
+            Kind::Uint(256) => Value::Uint256(U256::deserialize(deserializer)?),
+            Kind::Int(8) => Value::Int(i8::deserialize(deserializer)?.into()),
+            Kind::Int(16) => Value::Int(i16::deserialize(deserializer)?.into()),
+            Kind::Int(32) => Value::Int(i32::deserialize(deserializer)?.into()),
+            Kind::Int(64) => Value::Int(i64::deserialize(deserializer)?),
+            Kind::Uint(_) | Kind::Int(_) => return Err(de::Error::custom("unsupported integer")),
+            Kind::Bool => Value::Bool(bool::deserialize(deserializer)?),
+            Kind::String => Value::String(String::deserialize(deserializer)?),
+            Kind::Bytes => Value::Bytes(hex_bytes::deserialize(deserializer)?),
+            Kind::FixedBytes(len) => {
+                let word = FixedBytes::<32>::deserialize(deserializer)?;
+                if word.0[*len..].iter().any(|b| *b != 0) {
+                    return Err(de::Error::custom("fixed bytes are not padded with zeros"));
+                }
+                Value::FixedBytes(word.0[..*len].to_vec())
+            }
+            Kind::Address => Value::Address(Address::deserialize(deserializer)?),
+            Kind::Array(kind) => deserializer.deserialize_seq(ValuesVisitor {
+                kinds: Vec::new(),
+                element: Some(kind),
+            })?,
+            Kind::FixedArray(kind, len) => deserializer.deserialize_tuple(
+                *len,
+                ValuesVisitor {
+                    kinds: vec![kind; *len],
+                    element: None,
+                },
+            )?,
+            Kind::Tuple(kinds) => deserializer.deserialize_tuple(
+                kinds.len(),
+                ValuesVisitor {
+                    kinds: kinds.iter().collect(),
+                    element: None,
+                },
+            )?,
+        })
+    }
+}
+
+/// ValuesVisitor visits a dynamically sized array of `element`, or
+/// a tuple of `kinds` if there is no element
+struct ValuesVisitor<'a> {
+    kinds: Vec<&'a Kind>,
+    element: Option<&'a Kind>,
+}
+
+impl<'de, 'a> de::Visitor<'de> for ValuesVisitor<'a> {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence of values")
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut values = Vec::new();
+        match self.element {
+            Some(kind) => {
+                while let Some(value) = seq.next_element_seed(KindSeed(kind))? {
+                    values.push(value);
+                }
+                Ok(Value::Seq(values))
+            }
+            None => {
+                for (index, kind) in self.kinds.iter().enumerate() {
+                    let value = seq
+                        .next_element_seed(KindSeed(kind))?
+                        .ok_or_else(|| de::Error::invalid_length(index, &self))?;
+                    values.push(value);
+                }
+                Ok(Value::Tuple(values))
+            }
+        }
+    }
+}
+
+#[test]
+fn test_corpus() {
+    let corpus: serde_json::Value =
+        serde_json::from_str(include_str!("../testdata/corpus.json")).unwrap();
+    let cases = corpus.as_array().unwrap();
+    assert!(!cases.is_empty());
+
+    for case in cases {
+        let ty = case["type"].as_str().unwrap();
+        let encoded = case["encoded"].as_str().unwrap();
+        let kind = Kind::parse(ty);
+        let value = Value::from_json(&kind, &case["value"]);
+
+        assert_eq!(to_string(&value).unwrap(), encoded, "encoding {}", ty);
+
+        let decoded = from_str_seed(encoded, KindSeed(&kind))
+            .unwrap_or_else(|err| panic!("decoding {}: {}", ty, err));
+        assert_eq!(decoded, value, "decoding {}", ty);
+
+        let options = DeserializerOptions::new().strict(true);
+        let decoded = from_reader_seed(Cursor::new(encoded), KindSeed(&kind), options)
+            .unwrap_or_else(|err| panic!("strict decoding {}: {}", ty, err));
+        assert_eq!(decoded, value, "strict decoding {}", ty);
+    }
+}
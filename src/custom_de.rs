@@ -1,15 +1,18 @@
 use serde::de;
 
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
 use super::{
-    error::{Error, Result},
+    error::{Error, ErrorKind, Result},
     eth::Fixed,
 };
 
 macro_rules! unimplemented_de {
-    ( $($name:ident),+ ) => {
+    ( $($name:ident => $what:expr),+ ) => {
         $(
             fn $name<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
-                Err(Error::not_implemented())
+                Err(Error::not_implemented($what))
             }
         )*
     }
@@ -34,110 +37,131 @@ struct EthFixedDeserializer {
     content: Vec<u8>,
 }
 
+impl EthFixedDeserializer {
+    /// take returns the next `len` bytes of the content in the order
+    /// in which they are deserialized
+    fn take(&mut self, len: usize) -> Result<Vec<u8>> {
+        if self.remaining_bytes < len {
+            return Err(Error::new(
+                ErrorKind::InvalidLength,
+                "no more bytes left to deserialize in fixed size type",
+            ));
+        }
+
+        let mut bytes = Vec::with_capacity(len);
+        for _ in 0..len {
+            let byte = usize::try_from(self.offset)
+                .ok()
+                .and_then(|index| self.content.get(index))
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidLength,
+                        "no more bytes left to deserialize in fixed size type",
+                    )
+                })?;
+            bytes.push(*byte);
+            self.offset += self.offset_sign;
+        }
+
+        self.remaining_bytes -= len;
+        Ok(bytes)
+    }
+}
+
 impl<'de> de::Deserializer<'de> for &mut EthFixedDeserializer {
     type Error = Error;
 
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
     unimplemented_de!(
-        deserialize_any,
-        deserialize_bool,
-        deserialize_f32,
-        deserialize_f64,
-        deserialize_char,
-        deserialize_str,
-        deserialize_string,
-        deserialize_bytes,
-        deserialize_byte_buf,
-        deserialize_option,
-        deserialize_unit,
-        deserialize_seq,
-        deserialize_map,
-        deserialize_identifier,
-        deserialize_ignored_any
+        deserialize_any => "any",
+        deserialize_bool => "bool",
+        deserialize_f32 => "f32",
+        deserialize_f64 => "f64",
+        deserialize_char => "char",
+        deserialize_str => "str",
+        deserialize_string => "string",
+        deserialize_bytes => "bytes",
+        deserialize_byte_buf => "byte_buf",
+        deserialize_option => "option",
+        deserialize_unit => "unit",
+        deserialize_seq => "seq",
+        deserialize_map => "map",
+        deserialize_identifier => "identifier",
+        deserialize_ignored_any => "ignored_any"
     );
 
     fn deserialize_i8<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        if self.remaining_bytes < 1 {
-            panic!("received i8 when there are no more expected bytes to deserialize")
-        }
-
         match self.serializer_type {
-            Fixed::U256 => panic!("received i8 when deserializing U256"),
+            Fixed::U256 => Err(Error::new(
+                ErrorKind::Unsupported { what: "i8" },
+                "received i8 when deserializing U256",
+            )),
             Fixed::H256 | Fixed::H160 => {
-                self.remaining_bytes -= 1;
-                let value = self.content[self.offset as usize];
-                self.offset += self.offset_sign;
-                visitor.visit_i8(value as i8)
+                let bytes = self.take(1)?;
+                visitor.visit_i8(bytes[0] as i8)
             }
         }
     }
 
     fn deserialize_i16<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("i16"))
     }
 
     fn deserialize_i32<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("i32"))
     }
 
     fn deserialize_i64<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("i64"))
     }
 
     fn deserialize_u8<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        if self.remaining_bytes < 1 {
-            panic!("received u8 when there are no more expected bytes to deserialize")
-        }
-
         match self.serializer_type {
-            Fixed::U256 => panic!("received u8 when deserializing U256"),
+            Fixed::U256 => Err(Error::new(
+                ErrorKind::Unsupported { what: "u8" },
+                "received u8 when deserializing U256",
+            )),
             Fixed::H256 | Fixed::H160 => {
-                self.remaining_bytes -= 1;
-                let value = self.content[self.offset as usize];
-                self.offset += self.offset_sign;
-                visitor.visit_u8(value)
+                let bytes = self.take(1)?;
+                visitor.visit_u8(bytes[0])
             }
         }
     }
 
     fn deserialize_u16<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("u16"))
     }
 
     fn deserialize_u32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        if self.remaining_bytes < 4 {
-            panic!("received u8 when there are no more expected bytes to deserialize")
-        }
-
         match self.serializer_type {
-            Fixed::H256 | Fixed::H160 => panic!("received u32 when deserializing H256,H160"),
+            Fixed::H256 | Fixed::H160 => Err(Error::new(
+                ErrorKind::Unsupported { what: "u32" },
+                "received u32 when deserializing H256,H160",
+            )),
             Fixed::U256 => {
-                self.remaining_bytes -= 4;
                 let mut value = 0u32;
-                for byte_index in 0..4 {
-                    let index = (self.offset + byte_index * self.offset_sign) as usize;
-                    value += (self.content[index] as u32) << (8 * byte_index);
+                for (byte_index, byte) in self.take(4)?.into_iter().enumerate() {
+                    value += (byte as u32) << (8 * byte_index);
                 }
-                self.offset += 4 * self.offset_sign;
                 visitor.visit_u32(value)
             }
         }
     }
 
     fn deserialize_u64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        if self.remaining_bytes < 8 {
-            panic!("received u8 when there are no more expected bytes to deserialize")
-        }
-
         match self.serializer_type {
-            Fixed::H256 | Fixed::H160 => panic!("received u64 when deserializing H256,H160"),
+            Fixed::H256 | Fixed::H160 => Err(Error::new(
+                ErrorKind::Unsupported { what: "u64" },
+                "received u64 when deserializing H256,H160",
+            )),
             Fixed::U256 => {
-                self.remaining_bytes -= 8;
                 let mut value = 0u64;
-                for byte_index in 0..8 {
-                    let index = (self.offset + byte_index * self.offset_sign) as usize;
-                    value += (self.content[index] as u64) << (8 * byte_index);
+                for (byte_index, byte) in self.take(8)?.into_iter().enumerate() {
+                    value += (byte as u64) << (8 * byte_index);
                 }
-                self.offset += 8 * self.offset_sign;
                 visitor.visit_u64(value)
             }
         }
@@ -149,7 +173,7 @@ impl<'de> de::Deserializer<'de> for &mut EthFixedDeserializer {
         _len: usize,
         _visitor: V,
     ) -> Result<V::Value> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("tuple struct"))
     }
 
     fn deserialize_unit_struct<V: de::Visitor<'de>>(
@@ -157,7 +181,7 @@ impl<'de> de::Deserializer<'de> for &mut EthFixedDeserializer {
         _name: &'static str,
         _visitor: V,
     ) -> Result<V::Value> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("unit struct"))
     }
 
     fn deserialize_newtype_struct<V: de::Visitor<'de>>(
@@ -165,7 +189,7 @@ impl<'de> de::Deserializer<'de> for &mut EthFixedDeserializer {
         _name: &str,
         _visitor: V,
     ) -> Result<V::Value> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("newtype struct"))
     }
 
     fn deserialize_struct<V: de::Visitor<'de>>(
@@ -174,7 +198,7 @@ impl<'de> de::Deserializer<'de> for &mut EthFixedDeserializer {
         _fields: &'static [&'static str],
         _visitor: V,
     ) -> Result<V::Value> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("struct"))
     }
 
     fn deserialize_tuple<V: de::Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
@@ -187,7 +211,7 @@ impl<'de> de::Deserializer<'de> for &mut EthFixedDeserializer {
         _variants: &'static [&'static str],
         _visitor: V,
     ) -> Result<V::Value> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("enum"))
     }
 }
 
@@ -202,7 +226,7 @@ impl<'a> EthTupleAccess<'a> {
         EthTupleAccess {
             count: 0,
             remaining_bytes: de.remaining_bytes,
-            de: de,
+            de,
         }
     }
 }
@@ -215,10 +239,10 @@ impl<'de, 'a> de::SeqAccess<'de> for EthTupleAccess<'a> {
         seed: T,
     ) -> Result<Option<T::Value>> {
         if self.count >= self.remaining_bytes {
-            return Ok(None);
+            Ok(None)
         } else {
             self.count += 1;
-            seed.deserialize(&mut *self.de).map(|val| Some(val))
+            seed.deserialize(&mut *self.de).map(Some)
         }
     }
 }
@@ -230,20 +254,21 @@ pub struct EthFixedAccess {
 }
 
 impl EthFixedAccess {
-    pub fn new(content: Vec<u8>, serializer_type: Fixed) -> Self {
+    pub fn new(content: Vec<u8>, serializer_type: Fixed) -> Result<Self> {
         let (remaining_bytes, offset, offset_sign) = match serializer_type {
             Fixed::H256 => (32, 0, 1),
             Fixed::H160 => (20, 12, 1),
             Fixed::U256 => (32, 31, -1),
         };
 
-        assert_eq!(
-            content.len() == 32,
-            true,
-            "the expected number of bytes is 32"
-        );
+        if content.len() != 32 {
+            return Err(Error::new(
+                ErrorKind::InvalidLength,
+                "the expected number of bytes is 32",
+            ));
+        }
 
-        EthFixedAccess {
+        Ok(EthFixedAccess {
             count: 0,
             len: 1,
             de: EthFixedDeserializer {
@@ -253,7 +278,7 @@ impl EthFixedAccess {
                 serializer_type,
                 content,
             },
-        }
+        })
     }
 }
 
@@ -265,10 +290,10 @@ impl<'de> de::SeqAccess<'de> for EthFixedAccess {
         seed: T,
     ) -> Result<Option<T::Value>> {
         if self.count >= self.len {
-            return Ok(None);
+            Ok(None)
         } else {
             self.count += 1;
-            seed.deserialize(&mut self.de).map(|val| Some(val))
+            seed.deserialize(&mut self.de).map(Some)
         }
     }
 }
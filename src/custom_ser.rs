@@ -1,5 +1,7 @@
 use serde::ser;
 
+use alloc::string::String;
+
 use super::{
     error::{Error, Result},
     eth::Fixed,
@@ -59,7 +61,7 @@ impl BasicEthSerializer {
     }
 }
 
-impl<'a> ser::Serializer for &'a mut BasicEthSerializer {
+impl ser::Serializer for &mut BasicEthSerializer {
     type Ok = ();
     type Error = Error;
 
@@ -71,8 +73,12 @@ impl<'a> ser::Serializer for &'a mut BasicEthSerializer {
     type SerializeStruct = Self;
     type SerializeStructVariant = Self;
 
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
     fn serialize_bool(self, _value: bool) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("bool"))
     }
 
     fn serialize_i8(self, value: i8) -> Result<Self::Ok> {
@@ -87,15 +93,15 @@ impl<'a> ser::Serializer for &'a mut BasicEthSerializer {
     }
 
     fn serialize_i16(self, _value: i16) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("i16"))
     }
 
     fn serialize_i32(self, _value: i32) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("i32"))
     }
 
     fn serialize_i64(self, _value: i64) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("i64"))
     }
 
     fn serialize_u8(self, value: u8) -> Result<Self::Ok> {
@@ -110,7 +116,7 @@ impl<'a> ser::Serializer for &'a mut BasicEthSerializer {
     }
 
     fn serialize_u16(self, _value: u16) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("u16"))
     }
 
     fn serialize_u32(self, value: u32) -> Result<Self::Ok> {
@@ -142,39 +148,39 @@ impl<'a> ser::Serializer for &'a mut BasicEthSerializer {
     }
 
     fn serialize_f32(self, __value: f32) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("f32"))
     }
 
     fn serialize_f64(self, __value: f64) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("f64"))
     }
 
     fn serialize_char(self, _value: char) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("char"))
     }
 
     fn serialize_str(self, _value: &str) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("str"))
     }
 
     fn serialize_bytes(self, _value: &[u8]) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("bytes"))
     }
 
     fn serialize_none(self) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("none"))
     }
 
     fn serialize_some<T: ?Sized + ser::Serialize>(self, _value: &T) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("some"))
     }
 
     fn serialize_unit(self) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("unit"))
     }
 
     fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("unit struct"))
     }
 
     fn serialize_unit_variant(
@@ -183,7 +189,7 @@ impl<'a> ser::Serializer for &'a mut BasicEthSerializer {
         _variant_index: u32,
         _variant: &'static str,
     ) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("unit variant"))
     }
 
     fn serialize_newtype_struct<T: ?Sized + ser::Serialize>(
@@ -191,7 +197,7 @@ impl<'a> ser::Serializer for &'a mut BasicEthSerializer {
         _name: &'static str,
         _value: &T,
     ) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("newtype struct"))
     }
 
     fn serialize_newtype_variant<T: ?Sized + ser::Serialize>(
@@ -201,15 +207,15 @@ impl<'a> ser::Serializer for &'a mut BasicEthSerializer {
         _variant: &'static str,
         _value: &T,
     ) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("newtype variant"))
     }
 
     fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("seq"))
     }
 
     fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("tuple"))
     }
 
     fn serialize_tuple_struct(
@@ -217,7 +223,7 @@ impl<'a> ser::Serializer for &'a mut BasicEthSerializer {
         _name: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleStruct> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("tuple struct"))
     }
 
     fn serialize_tuple_variant(
@@ -227,15 +233,15 @@ impl<'a> ser::Serializer for &'a mut BasicEthSerializer {
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("tuple variant"))
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("map"))
     }
 
     fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("struct"))
     }
 
     fn serialize_struct_variant(
@@ -245,80 +251,80 @@ impl<'a> ser::Serializer for &'a mut BasicEthSerializer {
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStruct> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("struct variant"))
     }
 }
 
-impl<'a> ser::SerializeSeq for &'a mut BasicEthSerializer {
+impl ser::SerializeSeq for &mut BasicEthSerializer {
     type Ok = ();
     type Error = Error;
 
     fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, _value: &T) -> Result<()> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("seq"))
     }
 
     fn end(self) -> Result<()> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("seq"))
     }
 }
 
-impl<'a> ser::SerializeTuple for &'a mut BasicEthSerializer {
+impl ser::SerializeTuple for &mut BasicEthSerializer {
     type Ok = ();
     type Error = Error;
 
     fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, _value: &T) -> Result<()> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("tuple"))
     }
 
     fn end(self) -> Result<()> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("tuple"))
     }
 }
 
-impl<'a> ser::SerializeTupleStruct for &'a mut BasicEthSerializer {
+impl ser::SerializeTupleStruct for &mut BasicEthSerializer {
     type Ok = ();
     type Error = Error;
 
     fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, _value: &T) -> Result<()> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("tuple struct"))
     }
 
     fn end(self) -> Result<()> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("tuple struct"))
     }
 }
 
-impl<'a> ser::SerializeTupleVariant for &'a mut BasicEthSerializer {
+impl ser::SerializeTupleVariant for &mut BasicEthSerializer {
     type Ok = ();
     type Error = Error;
 
     fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, _value: &T) -> Result<()> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("tuple variant"))
     }
 
     fn end(self) -> Result<()> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("tuple variant"))
     }
 }
 
-impl<'a> ser::SerializeMap for &'a mut BasicEthSerializer {
+impl ser::SerializeMap for &mut BasicEthSerializer {
     type Ok = ();
     type Error = Error;
 
     fn serialize_key<T: ?Sized + ser::Serialize>(&mut self, _key: &T) -> Result<()> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("map"))
     }
 
     fn serialize_value<T: ?Sized + ser::Serialize>(&mut self, _value: &T) -> Result<()> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("map"))
     }
 
     fn end(self) -> Result<()> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("map"))
     }
 }
 
-impl<'a> ser::SerializeStruct for &'a mut BasicEthSerializer {
+impl ser::SerializeStruct for &mut BasicEthSerializer {
     type Ok = ();
     type Error = Error;
 
@@ -335,7 +341,7 @@ impl<'a> ser::SerializeStruct for &'a mut BasicEthSerializer {
     }
 }
 
-impl<'a> ser::SerializeStructVariant for &'a mut BasicEthSerializer {
+impl ser::SerializeStructVariant for &mut BasicEthSerializer {
     type Ok = ();
     type Error = Error;
 
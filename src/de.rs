@@ -1,18 +1,28 @@
 use serde::de;
 
-use std::{
-    collections::HashMap,
-    error::Error as stdError,
-    io::{Cursor, Read, Seek, SeekFrom},
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::{String, ToString},
     vec::Vec,
 };
+use core::{convert::TryFrom, marker::PhantomData};
+
+#[cfg(feature = "async")]
+use futures_util::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
 
 use super::{
     custom_de::EthFixedAccess,
-    error::{Category, Error, Result, TupleHint},
+    error::{Error, ErrorKind, Result, TupleHint},
     eth,
+    io::{Cursor, Read, Seek, SeekFrom},
 };
 
+/// STATIC_TUPLE and DYNAMIC_TUPLE are the names of the tuple structs
+/// that seeds deserialize when the type tells whether a tuple is static
+/// or dynamic, so that the deserializer does not have to guess it
+pub(crate) const STATIC_TUPLE: &str = "$serde_eth::StaticTuple";
+pub(crate) const DYNAMIC_TUPLE: &str = "$serde_eth::DynamicTuple";
+
 #[derive(Debug, Clone)]
 pub enum BaseType {
     Static,
@@ -38,23 +48,58 @@ pub struct Scope {
     /// to find out if a tuple should be decoded as a dynamic tuple
     /// or not in case of doubt
     types: Vec<BaseType>,
+
+    /// Tails of the dynamic items in the scope, in the order in which
+    /// their offsets are in the head. In strict mode they are kept for
+    /// every item to verify that the encoding is canonical, otherwise
+    /// only for tuples that were guessed to be dynamic
+    tails: Vec<Tail>,
+}
+
+#[derive(Debug)]
+struct Tail {
+    /// Offset of the tail relative to the beginning of the scope
+    offset: usize,
+
+    /// Number of bytes of the tail
+    size: usize,
+
+    /// Index of the tuple if the tail belongs to a tuple that was guessed
+    /// to be dynamic, so that a hint can be given if the guess was wrong
+    tuple_index: Option<u64>,
 }
 
 impl Scope {
     fn new(offset: usize) -> Self {
         Scope {
-            offset: offset,
+            offset,
             read_head: 0,
             read_tail: 0,
             types: Vec::new(),
+            tails: Vec::new(),
         }
     }
 
     fn has_dynamic_types(&self) -> bool {
-        self.types.iter().any(|t| match t {
-            BaseType::Dynamic => true,
-            _ => false,
-        })
+        self.types.iter().any(|t| matches!(t, BaseType::Dynamic))
+    }
+}
+
+/// PathSegment is an element of the path to the value that
+/// is being deserialized, which is reported in errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum PathSegment {
+    Field(&'static str),
+    Index(usize),
+    Element(usize),
+}
+
+impl PathSegment {
+    fn new(fields: Option<&'static [&'static str]>, index: usize) -> Self {
+        match fields.and_then(|fields| fields.get(index)) {
+            Some(field) => PathSegment::Field(field),
+            None => PathSegment::Index(index),
+        }
     }
 }
 
@@ -85,11 +130,11 @@ macro_rules! static_scope {
     ( $de: expr, $fn: expr ) => {{
         scoped![$de, BaseType::Static, |_offset| {
             let offset = $de.seek(SeekFrom::Current(0))?;
-            $de.push_scope(Scope::new(offset as usize));
+            $de.enter_scope(Scope::new(offset as usize))?;
 
             let res = $fn()?;
 
-            let scope = $de.scope.pop().unwrap();
+            let scope = $de.exit_scope()?;
             $de.seek(SeekFrom::Start(offset + scope.read_head as u64))?;
 
             Ok((scope.read_head + scope.read_tail, 0, res))
@@ -100,14 +145,21 @@ macro_rules! static_scope {
 macro_rules! dynamic_scope {
     ( $de: expr, $fn: expr ) => {{
         scoped![$de, BaseType::Dynamic, |offset| {
-            let content_offset = $de.read_uint_head(64)?;
-            let offset = offset + (content_offset << 1);
-            let offset = $de.seek(SeekFrom::Start(offset as u64))?;
+            let content_offset = relative_offset($de.read_uint_head(64)?)?;
+            let offset = absolute_offset(offset, content_offset)?;
+            let offset = $de.seek(SeekFrom::Start(offset))?;
             let len = $de.read_uint_tail(64)?;
-            $de.push_scope(Scope::new(64 + offset as usize));
+            $de.enter_scope(Scope::new(64 + offset as usize))?;
 
             let res = $fn(len)?;
-            let scope = $de.scope.pop().unwrap();
+            let scope = $de.exit_scope()?;
+
+            let size = 64 + scope.read_tail + scope.read_head;
+            $de.add_tail(Tail {
+                offset: content_offset as usize,
+                size,
+                tuple_index: None,
+            })?;
 
             Ok((0, scope.read_tail + scope.read_head, res))
         }]
@@ -117,7 +169,8 @@ macro_rules! dynamic_scope {
 macro_rules! array_stack {
     ( $de:expr, $x:expr ) => {{
         if ($de.remaining_size as usize) < $x {
-            return Err(Error::message(
+            return Err(Error::new(
+                ErrorKind::LimitExceeded,
                 "deserializer does not have enough space to \
                  allocate a vector in the stack",
             ));
@@ -130,7 +183,8 @@ macro_rules! array_stack {
 macro_rules! vec_heap {
     ( $de:expr, $x:expr ) => {{
         if ($de.remaining_size as usize) < $x {
-            return Err(Error::message(
+            return Err(Error::new(
+                ErrorKind::LimitExceeded,
                 "deserializer does not have enough space to \
                  allocate a vector in the heap",
             ));
@@ -156,6 +210,18 @@ pub struct Deserializer<'r, R> {
     /// A deserializer for custom types if required.
     current_custom_deserializer: Option<eth::Fixed>,
 
+    /// Names of the fields of the struct that is about to be
+    /// deserialized as a tuple, if any
+    current_fields: Option<&'static [&'static str]>,
+
+    /// Path to the value that is being deserialized. Segments are
+    /// only removed once a value is deserialized successfully, so
+    /// on error it points to the value that failed
+    path: Vec<PathSegment>,
+
+    /// Offset in the input of the last read
+    last_offset: u64,
+
     /// A reader seeker that has the content deserialized by the
     /// deserializer
     read: &'r mut RefReadSeek<R>,
@@ -164,42 +230,338 @@ pub struct Deserializer<'r, R> {
     /// deserializing tuples. It can be used to attempt to deserialize
     /// the same tuple either as a fixed array or a dynamically sized
     /// tuple
-    tuple_hints: HashMap<u64, BaseType>,
+    tuple_hints: BTreeMap<u64, BaseType>,
+
+    /// Tuples that were guessed to be dynamic without a hint, in the
+    /// order in which they were found. If decoding fails, the guess
+    /// may be the reason even if the error happens later on
+    guessed_tuples: Vec<u64>,
+
+    /// Tuples that a hint proved to be static, by their path with the
+    /// indices of array elements left out. The elements of an array
+    /// have the same type, so the tuples at the same path in the
+    /// elements that follow are static too and are not guessed again
+    static_tuples: BTreeSet<Vec<PathSegment>>,
+
+    /// Length of the input, once it is needed to check that the offset
+    /// of a tuple guessed to be dynamic points inside of it
+    input_len: Option<u64>,
 
     /// Keeps track of the current scope that is being deserialized.
     /// Every sequence, or dynamically sized tuple adds a scope, and it's
     /// treated as a stack
     scope: Vec<Scope>,
+
+    /// Limits set by the user for the deserialization
+    options: DeserializerOptions,
 }
 
-pub struct DeserializerProperties {
-    tuple_hints: HashMap<u64, BaseType>,
+/// DeserializerOptions sets the limits the deserializer enforces on
+/// its input. They are useful to decode untrusted data without letting
+/// it make the deserializer allocate or read too much.
+///
+/// ```
+/// use serde_eth::de::DeserializerOptions;
+///
+/// let options = DeserializerOptions::new()
+///     .max_size(1 << 20)
+///     .max_alloc(1 << 16)
+///     .max_depth(16)
+///     .max_array_len(1024);
+///
+/// let value: Vec<u8> = serde_eth::from_str_with_options(
+///     "0000000000000000000000000000000000000000000000000000000000000020\
+///      0000000000000000000000000000000000000000000000000000000000000000",
+///     options,
+/// )
+/// .unwrap();
+/// assert!(value.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DeserializerOptions {
     max_size: u64,
+    max_alloc: u64,
+    max_depth: usize,
+    max_array_len: u64,
+    max_retries: usize,
+    strict: bool,
+    params: bool,
+}
+
+impl DeserializerOptions {
+    pub fn new() -> Self {
+        DeserializerOptions {
+            max_size: 1 << 24,
+            max_alloc: 1 << 24,
+            max_depth: 128,
+            max_array_len: u64::MAX,
+            max_retries: 64,
+            strict: false,
+            params: false,
+        }
+    }
+
+    /// max_size sets the maximum number of bytes the deserializer reads
+    /// from the input. Bytes that are read more than once, e.g. when
+    /// several offsets point to the same content, count every time
+    pub fn max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// max_alloc sets the maximum length in bytes of a single dynamically
+    /// sized item, like a string or a byte array
+    pub fn max_alloc(mut self, max_alloc: u64) -> Self {
+        self.max_alloc = max_alloc;
+        self
+    }
+
+    /// max_depth sets the maximum nesting depth of sequences and tuples.
+    /// Decoding recursive types recurses once per nesting level, so this
+    /// limit is what keeps crafted input from exhausting the stack.
+    /// Defaults to 128
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// max_array_len sets the maximum number of elements of a dynamically
+    /// sized array
+    pub fn max_array_len(mut self, max_array_len: u64) -> Self {
+        self.max_array_len = max_array_len;
+        self
+    }
+
+    /// max_retries sets how many times decoding is attempted again after
+    /// a tuple was wrongly guessed to be dynamic. Every attempt decodes
+    /// the input from the start, so this bounds the work that crafted
+    /// input with many such tuples causes. A tuple proven to be static
+    /// is not guessed again in the other elements of its array, so it
+    /// takes one retry however long the array is. `SeqIter` only
+    /// decodes the element again, and applies the limit to every
+    /// element. Defaults to 64
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// strict makes the deserializer reject any input that is not
+    /// exactly what the serializer produces for the decoded value:
+    /// offsets must point right after the previous item without gaps
+    /// or overlaps, padding must be zero and hex digits lowercase.
+    /// This way the input can be trusted to be the only encoding of
+    /// the value, e.g. when it is hashed or signed
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// params makes the deserializer read a tuple at the root as the
+    /// list of parameters of a function call, which is not preceded by
+    /// an offset even if the tuple is dynamic
+    pub fn params(mut self, params: bool) -> Self {
+        self.params = params;
+        self
+    }
+}
+
+impl Default for DeserializerOptions {
+    fn default() -> Self {
+        DeserializerOptions::new()
+    }
+}
+
+pub(crate) struct DeserializerProperties {
+    tuple_hints: BTreeMap<u64, BaseType>,
+    options: DeserializerOptions,
 }
 
 impl<'r, R: Read + Seek> Deserializer<'r, R> {
     pub fn new(read: &'r mut RefReadSeek<R>) -> Self {
+        Deserializer::with_options(read, DeserializerOptions::default())
+    }
+
+    pub fn with_options(read: &'r mut RefReadSeek<R>, options: DeserializerOptions) -> Self {
         Deserializer::with_props(
             read,
             DeserializerProperties {
-                tuple_hints: HashMap::new(),
-                max_size: 0,
+                tuple_hints: BTreeMap::new(),
+                options,
             },
         )
     }
 
-    pub fn with_props(read: &'r mut RefReadSeek<R>, props: DeserializerProperties) -> Self {
+    pub(crate) fn with_props(read: &'r mut RefReadSeek<R>, props: DeserializerProperties) -> Self {
         Deserializer {
-            remaining_size: if props.max_size == 0 {
-                1 << 24
-            } else {
-                props.max_size
-            },
+            remaining_size: props.options.max_size,
             tuple_counter: 0,
             current_custom_deserializer: None,
+            current_fields: None,
+            path: Vec::new(),
+            last_offset: 0,
             read,
             tuple_hints: props.tuple_hints,
+            guessed_tuples: Vec::new(),
+            static_tuples: BTreeSet::new(),
+            input_len: None,
             scope: Vec::new(),
+            options: props.options,
+        }
+    }
+
+    /// enter_scope pushes a new nested scope, failing if the maximum
+    /// nesting depth is reached
+    fn enter_scope(&mut self, scope: Scope) -> Result<()> {
+        if self.scope.len() >= self.options.max_depth {
+            return Err(Error::new(
+                ErrorKind::LimitExceeded,
+                "maximum nesting depth exceeded",
+            ));
+        }
+
+        self.scope.push(scope);
+        Ok(())
+    }
+
+    /// exit_scope pops the current scope. In strict mode it also
+    /// verifies that the tails of the dynamic items in the scope come
+    /// right after the head, in order and without gaps between them
+    fn exit_scope(&mut self) -> Result<Scope> {
+        let scope = self.scope.pop().ok_or_else(|| {
+            Error::new(
+                ErrorKind::Custom,
+                "attempt to exit scope without entering one",
+            )
+        })?;
+
+        if self.options.strict {
+            let mut expected = scope.read_head;
+            for tail in scope.tails.iter() {
+                if tail.offset != expected {
+                    return Err(non_canonical_offset(tail.tuple_index));
+                }
+                expected += tail.size;
+            }
+        } else if let Some(tail) = scope.tails.iter().find(|t| t.offset < scope.read_head) {
+            // the tails always come after the head, so an offset pointing
+            // into the head means the tuple was not dynamic after all
+            return Err(head_offset_error(tail.tuple_index));
+        }
+
+        Ok(scope)
+    }
+
+    /// head_offset returns the absolute offset of the next head
+    /// to be read in the current scope
+    fn head_offset(&self) -> Result<usize> {
+        self.scope
+            .last()
+            .map(|scope| scope.offset + scope.read_head)
+            .ok_or_else(|| Error::new(ErrorKind::Custom, "no scope to read the next head from"))
+    }
+
+    /// add_tail keeps track of the tail of a dynamic item in the current
+    /// scope. A dynamic item without a parent scope is the top level
+    /// item, and its content always follows its offset
+    fn add_tail(&mut self, tail: Tail) -> Result<()> {
+        if !self.options.strict && tail.tuple_index.is_none() {
+            return Ok(());
+        }
+
+        match self.scope.last_mut() {
+            Some(scope) => {
+                scope.tails.push(tail);
+                Ok(())
+            }
+            None if tail.offset == 64 => Ok(()),
+            None if self.options.strict => Err(non_canonical_offset(tail.tuple_index)),
+            None if tail.offset > 64 => Ok(()),
+            None => Err(head_offset_error(tail.tuple_index)),
+        }
+    }
+
+    /// locate adds to the error the position in the input at which
+    /// the deserializer failed
+    fn locate(&mut self, error: Error) -> Error {
+        let scopes = self.scope.iter().map(|scope| scope.offset).collect();
+        let mut path = String::new();
+        for segment in self.path.iter() {
+            match segment {
+                PathSegment::Field(field) => {
+                    path.push('.');
+                    path.push_str(field);
+                }
+                PathSegment::Index(index) | PathSegment::Element(index) => {
+                    path.push_str(&format!("[{}]", index))
+                }
+            }
+        }
+
+        error.with_position(self.last_offset, scopes, path)
+    }
+
+    /// check_retries fails when decoding was attempted again `retries`
+    /// times, which is more than the options allow
+    fn check_retries(&self, retries: usize) -> Result<()> {
+        if retries > self.options.max_retries {
+            return Err(Error::new(
+                ErrorKind::LimitExceeded,
+                "maximum number of retries to guess tuples exceeded",
+            ));
+        }
+        Ok(())
+    }
+
+    /// points_into_input returns true if an offset relative to the scope
+    /// that is being read points inside the input
+    fn points_into_input(&mut self, offset: u64) -> Result<bool> {
+        let scope_offset = self.scope.last().map_or(0, |scope| scope.offset as u64);
+        let pos = match offset
+            .checked_mul(2)
+            .and_then(|offset| offset.checked_add(scope_offset))
+        {
+            Some(pos) => pos,
+            None => return Ok(false),
+        };
+
+        let input_len = match self.input_len {
+            Some(input_len) => input_len,
+            None => {
+                let input_len = self.read.len()?;
+                self.input_len = Some(input_len);
+                input_len
+            }
+        };
+        Ok(pos < input_len)
+    }
+
+    /// tuple_type_path returns the path of the tuple that is being
+    /// deserialized, which is the same for the tuples of every element
+    /// of an array
+    fn tuple_type_path(&self) -> Vec<PathSegment> {
+        self.path
+            .iter()
+            .map(|segment| match segment {
+                PathSegment::Element(_) => PathSegment::Element(0),
+                segment => *segment,
+            })
+            .collect()
+    }
+
+    /// retry_hint returns the hint with which decoding is attempted
+    /// again after failing with `err`, if there is any left to try
+    fn retry_hint(&self, err: &Error) -> Option<TupleHint> {
+        match err.tuple_hint() {
+            Some(hint) if !self.tuple_hints.contains_key(&hint.index) => Some(hint),
+            Some(_) => None,
+            // an error without a hint can still be caused by a tuple
+            // wrongly guessed to be dynamic, so the last guess is
+            // attempted as a static tuple
+            None if !err.is_io() => self
+                .guessed_tuples
+                .last()
+                .map(|index| TupleHint::new(*index, false)),
+            None => None,
         }
     }
 
@@ -217,11 +579,14 @@ impl<'r, R: Read + Seek> Deserializer<'r, R> {
 
     fn read_exact_to_end(&mut self, bytes: &mut [u8]) -> Result<()> {
         if self.remaining_size < bytes.len() as u64 {
-            return Err(Error::message(
+            return Err(Error::new(
+                ErrorKind::LimitExceeded,
                 "deserializer does not have remaining space to parse more data",
             ));
         }
 
+        self.last_offset = self.read.position()?;
+
         let mut total_bytes_read = 0usize;
         while total_bytes_read < bytes.len() {
             let bytes_read = self.read.read(&mut bytes[total_bytes_read..])?;
@@ -233,62 +598,105 @@ impl<'r, R: Read + Seek> Deserializer<'r, R> {
 
         if total_bytes_read == bytes.len() {
             self.remaining_size -= bytes.len() as u64;
+            if self.options.strict && bytes.iter().any(|b| b.is_ascii_uppercase()) {
+                return Err(Error::new(
+                    ErrorKind::NonCanonical,
+                    "non canonical encoding: hex digits must be lowercase",
+                ));
+            }
             Ok(())
         } else {
-            Err(Error::message("insufficient bytes read from reader"))
+            Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "insufficient bytes read from reader",
+            ))
         }
     }
 
     pub fn end(&mut self) -> Result<()> {
+        // the probe for trailing data is not charged against max_size, so
+        // that a value that uses up exactly max_size is still checked
+        self.last_offset = self.read.position()?;
         let mut bytes = [0u8; 1];
-        let res = self.read_exact_to_end(&mut bytes);
-
-        match res {
+        match self.read.read(&mut bytes)? {
+            0 => Ok(()),
             // Being able to read any additional bytes means that stream was not at end.
-            Ok(_) => Err(Error::parsing("input has not been processed completely")),
-            Err(err) => match err.classify() {
-                Category::Data => Ok(()),
-                _ => Err(Error::parsing("failed to verify if reader is empty")),
-            },
+            _ => Err(Error::new(
+                ErrorKind::TrailingData,
+                "input has not been processed completely",
+            )),
         }
     }
 
     fn read_char(&mut self) -> Result<char> {
         let bytes = self.read_byte_array()?;
         if bytes.len() > 4 {
-            return Err(Error::parsing(
+            return Err(Error::new(
+                ErrorKind::InvalidLength,
                 "parsed char from byte array longer than 4 bytes",
             ));
         }
 
-        match std::str::from_utf8(&bytes[..]) {
-            Err(_) => Err(Error::parsing("parsed byte array cannot decode to a char")),
-            Ok(s) => s
-                .chars()
-                .next()
-                .ok_or_else(|| Error::parsing("parsed byte array cannot decode to a char")),
+        match core::str::from_utf8(&bytes[..]) {
+            Err(_) => Err(Error::new(
+                ErrorKind::InvalidUtf8,
+                "parsed byte array cannot decode to a char",
+            )),
+            Ok(s) => s.chars().next().ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidUtf8,
+                    "parsed byte array cannot decode to a char",
+                )
+            }),
         }
     }
 
     fn read_str(&mut self) -> Result<String> {
         let bytes = self.read_byte_array()?;
-        match std::str::from_utf8(&bytes[..]) {
+        match core::str::from_utf8(&bytes[..]) {
             Ok(s) => Ok(s.to_string()),
-            Err(_) => Err(Error::parsing("parsed byte array cannot decode to a char")),
+            Err(_) => Err(Error::new(
+                ErrorKind::InvalidUtf8,
+                "parsed byte array cannot decode to a char",
+            )),
         }
     }
 
     fn read_byte_array(&mut self) -> Result<Vec<u8>> {
         dynamic_scope![self, |len| {
-            let bytes_read = len << 1;
+            if len > self.options.max_alloc {
+                return Err(Error::new(
+                    ErrorKind::LimitExceeded,
+                    "dynamic item exceeds the maximum allocation size",
+                ));
+            }
+
+            let bytes_read = u64::checked_mul(len, 2).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::LimitExceeded,
+                    "dynamic item exceeds the maximum allocation size",
+                )
+            })?;
 
             // only read multiple of 64 bytes
-            let base = ((bytes_read >> 6) << 6) as u64;
-            let remain = if bytes_read - base == 0u64 { 0 } else { 1 };
-            let read_len = base + (remain << 6);
+            let base = (bytes_read >> 6) << 6;
+            let remain: u64 = if bytes_read - base == 0u64 { 0 } else { 1 };
+            let read_len = base.checked_add(remain << 6).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::LimitExceeded,
+                    "dynamic item exceeds the maximum allocation size",
+                )
+            })?;
             let mut read_data = vec_heap![self, (read_len as usize)];
             self.read_bytes_tail(&mut read_data[..])?;
 
+            if self.options.strict && read_data[bytes_read as usize..].iter().any(|b| *b != b'0') {
+                return Err(Error::new(
+                    ErrorKind::NonCanonical,
+                    "non canonical encoding: padding bytes must be zero",
+                ));
+            }
+
             eth::decode_bytes(&read_data, len as usize)
         }]
     }
@@ -318,38 +726,87 @@ impl<'r, R: Read + Seek> Deserializer<'r, R> {
     fn read_static_size_tuple<'de, V: de::Visitor<'de>>(
         &mut self,
         len: usize,
+        fields: Option<&'static [&'static str]>,
         visitor: V,
     ) -> Result<V::Value> {
         // the cursor of the reader should already be at the beginning
         // of the tuple. Since a static tuple does not have offsets,
         // we can just take that address as offset
         static_scope![self, || visitor
-            .visit_seq(StaticTupleAccess::new(self, len as usize))]
+            .visit_seq(StaticTupleAccess::new(self, len, fields))]
     }
 
     fn read_dynamic_size_tuple<'de, V: de::Visitor<'de>>(
         &mut self,
         len: usize,
+        fields: Option<&'static [&'static str]>,
         visitor: V,
     ) -> Result<V::Value> {
+        let tuple_index = if self.tuple_hints.contains_key(&self.tuple_counter) {
+            None
+        } else {
+            Some(self.tuple_counter)
+        };
+
         // the implementation of this is almost as a dynamic_scope,
         // but a tuple does not have a len parameter in its binary
         // representation
         scoped![self, BaseType::Dynamic, |offset| {
-            let tuple_offset = self.read_uint_head(64)?;
-            let offset = (tuple_offset << 1) + offset as u64;
+            let tuple_offset = relative_offset(self.read_uint_head(64)?)?;
+            let offset = absolute_offset(offset, tuple_offset)?;
 
-            let curr = self.seek(SeekFrom::Start(offset as u64))?;
+            let curr = self.seek(SeekFrom::Start(offset))?;
             let scope = Scope::new(curr as usize);
-            self.push_scope(scope);
-            let res = visitor.visit_seq(DynamicTupleAccess::new(self, len as usize))?;
-            let scope = self.scope.pop().unwrap();
+            self.enter_scope(scope)?;
+            let res = visitor.visit_seq(DynamicTupleAccess::new(self, len, fields, tuple_index))?;
+            let scope = self.exit_scope()?;
+
+            // a tuple is only dynamic if some of its elements are dynamic
+            if let Some(index) = tuple_index {
+                if !scope.has_dynamic_types() {
+                    return Err(Error::hint(
+                        TupleHint::new(index, false),
+                        Error::new(
+                            ErrorKind::InvalidOffset,
+                            "tuple without dynamic elements has an offset",
+                        ),
+                    ));
+                }
+            }
+
             self.seek(SeekFrom::Current(scope.read_tail as i64))?;
 
-            Ok((scope.read_head, 0, res))
+            self.add_tail(Tail {
+                offset: tuple_offset as usize,
+                size: scope.read_head + scope.read_tail,
+                tuple_index,
+            })?;
+
+            // the head of the tuple in the parent scope is just the
+            // offset, the content of the tuple is part of the tail
+            Ok((0, scope.read_head + scope.read_tail, res))
         }]
     }
 
+    fn read_params_tuple<'de, V: de::Visitor<'de>>(
+        &mut self,
+        len: usize,
+        fields: Option<&'static [&'static str]>,
+        visitor: V,
+    ) -> Result<V::Value> {
+        // the parameters are read as a dynamic tuple whose content
+        // starts right away instead of at an offset
+        let offset = self.seek(SeekFrom::Current(0))?;
+        self.enter_scope(Scope::new(offset as usize))?;
+        let res = visitor.visit_seq(DynamicTupleAccess::new(self, len, fields, None))?;
+        let scope = self.exit_scope()?;
+        self.seek(SeekFrom::Start(
+            offset + (scope.read_head + scope.read_tail) as u64,
+        ))?;
+
+        Ok(res)
+    }
+
     fn read_custom_tuple<'de, V: de::Visitor<'de>>(
         &mut self,
         _len: usize,
@@ -360,7 +817,17 @@ impl<'r, R: Read + Seek> Deserializer<'r, R> {
             let mut bytes = array_stack![self, 64];
             self.read_bytes_head(&mut bytes[..])?;
             let bytes = eth::decode_bytes(&bytes[..], 32)?;
-            visitor.visit_seq(EthFixedAccess::new(bytes, t))
+            if self.options.strict {
+                if let eth::Fixed::H160 = t {
+                    if bytes[..12].iter().any(|b| *b != 0) {
+                        return Err(Error::new(
+                            ErrorKind::NonCanonical,
+                            "non canonical encoding: padding bytes must be zero",
+                        ));
+                    }
+                }
+            }
+            visitor.visit_seq(EthFixedAccess::new(bytes, t)?)
         }]
     }
 
@@ -405,8 +872,12 @@ impl<'r, R: Read + Seek> Deserializer<'r, R> {
 impl<'r, 'de, 'a, R: Read + Seek> de::Deserializer<'de> for &'a mut Deserializer<'r, R> {
     type Error = Error;
 
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
     fn deserialize_any<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("any"))
     }
 
     fn deserialize_bool<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
@@ -442,7 +913,7 @@ impl<'r, 'de, 'a, R: Read + Seek> de::Deserializer<'de> for &'a mut Deserializer
     fn deserialize_i64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         static_scope![self, || {
             let value = self.read_int_head(64)?;
-            visitor.visit_i64(value as i64)
+            visitor.visit_i64(value)
         }]
     }
 
@@ -470,16 +941,16 @@ impl<'r, 'de, 'a, R: Read + Seek> de::Deserializer<'de> for &'a mut Deserializer
     fn deserialize_u64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         static_scope![self, || {
             let value = self.read_uint_head(64)?;
-            visitor.visit_u64(value as u64)
+            visitor.visit_u64(value)
         }]
     }
 
     fn deserialize_f32<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("f32"))
     }
 
     fn deserialize_f64<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("f64"))
     }
 
     fn deserialize_char<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
@@ -514,7 +985,8 @@ impl<'r, 'de, 'a, R: Read + Seek> de::Deserializer<'de> for &'a mut Deserializer
             match len {
                 0 => visitor.visit_none(),
                 1 => visitor.visit_some(&mut *self),
-                _ => Err(Error::message(
+                _ => Err(Error::new(
+                    ErrorKind::InvalidLength,
                     "an option should be serialized as an \
                      array of size either 0 or 1",
                 )),
@@ -544,60 +1016,100 @@ impl<'r, 'de, 'a, R: Read + Seek> de::Deserializer<'de> for &'a mut Deserializer
     }
 
     fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        dynamic_scope![self, |len| visitor
-            .visit_seq(SeqAccess::new(self, len as usize))]
+        dynamic_scope![self, |len| {
+            if len > self.options.max_array_len {
+                return Err(Error::new(
+                    ErrorKind::LimitExceeded,
+                    "array length exceeds the maximum array length",
+                ));
+            }
+
+            visitor.visit_seq(SeqAccess::new(self, len as usize))
+        }]
     }
 
     fn deserialize_tuple<V: de::Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
         self.tuple_counter += 1;
+        let fields = self.current_fields.take();
 
         if let Some(t) = self.current_custom_deserializer {
             self.current_custom_deserializer = None;
             return self.read_custom_tuple(len, t, visitor);
         }
 
+        // the parameters of a function call are a tuple at the root
+        // without an offset, so there is nothing to guess
+        if self.options.params && self.scope.is_empty() && self.tuple_counter == 1 {
+            return self.read_params_tuple(len, fields, visitor);
+        }
+
         // for tuples the deserialization is ambiguous. If the user has passed
         // a hint, used the hint to deserialize the tuple with that index
         let hint = self.tuple_hints.get(&self.tuple_counter);
 
         match hint {
             Some(h) => match h {
-                BaseType::Static => self.read_static_size_tuple(len, visitor),
-                BaseType::Dynamic => self.read_dynamic_size_tuple(len, visitor),
+                BaseType::Static => {
+                    let path = self.tuple_type_path();
+                    self.static_tuples.insert(path);
+                    self.read_static_size_tuple(len, fields, visitor)
+                }
+                BaseType::Dynamic => self.read_dynamic_size_tuple(len, fields, visitor),
             },
+            None if !self.static_tuples.is_empty()
+                && self.static_tuples.contains(&self.tuple_type_path()) =>
+            {
+                self.read_static_size_tuple(len, fields, visitor)
+            }
             None => {
                 // in case there's no hint, the assumption is the following:
                 // if the first integer in the tuple is multiple of 32 it could be an offset,
                 // in which case it would be a dynamic sized tuple. In case it is not multiple
                 // of 32, for sure it is not an offset, in which case can be safely
-                // deserialized as a static sized tuple.
+                // deserialized as a static sized tuple. An offset also has to point past
+                // the head in which it is, since the tails come after the head, and
+                // inside the input.
                 let res = self.peek_uint(64);
+                let read_head = self.scope.last().map_or(0, |scope| scope.read_head as u64);
 
                 if let Ok(tuple_offset) = res {
-                    if tuple_offset % 32 == 0 {
+                    if tuple_offset % 32 == 0
+                        && tuple_offset.saturating_mul(2) > read_head
+                        && self.points_into_input(tuple_offset)?
+                    {
                         // This is just a guess, it can be that this fails, in which case
                         // an error with TupleHint will be raised so that the deserialization
                         // can be attempted again
-                        return self.read_dynamic_size_tuple(len, visitor);
+                        self.guessed_tuples.push(self.tuple_counter);
+                        return self.read_dynamic_size_tuple(len, fields, visitor);
                     }
                 }
 
-                self.read_static_size_tuple(len, visitor)
+                self.read_static_size_tuple(len, fields, visitor)
             }
         }
     }
 
     fn deserialize_tuple_struct<V: de::Visitor<'de>>(
         self,
-        _name: &'static str,
+        name: &'static str,
         len: usize,
         visitor: V,
     ) -> Result<V::Value> {
+        // the tuple that is deserialized next is hinted with its type
+        let hint = match name {
+            STATIC_TUPLE => Some(BaseType::Static),
+            DYNAMIC_TUPLE => Some(BaseType::Dynamic),
+            _ => None,
+        };
+        if let Some(hint) = hint {
+            self.tuple_hints.insert(self.tuple_counter + 1, hint);
+        }
         self.deserialize_tuple(len, visitor)
     }
 
     fn deserialize_map<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("map"))
     }
 
     fn deserialize_struct<V: de::Visitor<'de>>(
@@ -606,6 +1118,7 @@ impl<'r, 'de, 'a, R: Read + Seek> de::Deserializer<'de> for &'a mut Deserializer
         fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value> {
+        self.current_fields = Some(fields);
         self.deserialize_tuple(fields.len(), visitor)
     }
 
@@ -651,19 +1164,18 @@ impl<'de, 'r, 'a, R: Read + Seek + 'r> de::SeqAccess<'de> for SeqAccess<'r, 'a,
             return Ok(None);
         }
 
-        let res = seed.deserialize(&mut *self.de);
-
-        let scope = self.de.pop_scope().unwrap();
-        let new_offset = scope.offset + scope.read_head;
+        self.de.path.push(PathSegment::Element(self.count - 1));
+        let value = seed.deserialize(&mut *self.de)?;
+        self.de.path.pop();
 
         if self.count < self.len {
             // if there are still elements in the sequence, seek back to the next
             // items's head
+            let new_offset = self.de.head_offset()?;
             self.de.seek(SeekFrom::Start(new_offset as u64))?;
         }
 
-        self.de.push_scope(scope);
-        res.map(|value| Some(value))
+        Ok(Some(value))
     }
 }
 
@@ -714,12 +1226,22 @@ impl<'de, 'r, 'a, R: Read + Seek + 'r> de::VariantAccess<'de> for EnumAccess<'r,
 struct StaticTupleAccess<'r, 'a, R: 'r> {
     len: usize,
     count: usize,
+    fields: Option<&'static [&'static str]>,
     de: &'a mut Deserializer<'r, R>,
 }
 
 impl<'r, 'a, R> StaticTupleAccess<'r, 'a, R> {
-    fn new(de: &'a mut Deserializer<'r, R>, len: usize) -> Self {
-        StaticTupleAccess { len, count: 0, de }
+    fn new(
+        de: &'a mut Deserializer<'r, R>,
+        len: usize,
+        fields: Option<&'static [&'static str]>,
+    ) -> Self {
+        StaticTupleAccess {
+            len,
+            count: 0,
+            fields,
+            de,
+        }
     }
 }
 
@@ -735,60 +1257,72 @@ impl<'de, 'r, 'a, R: Read + Seek + 'r> de::SeqAccess<'de> for StaticTupleAccess<
             return Ok(None);
         }
 
-        let res = seed.deserialize(&mut *self.de);
-
-        let scope = self.de.pop_scope().unwrap();
-        let new_offset = scope.offset + scope.read_head;
+        self.de
+            .path
+            .push(PathSegment::new(self.fields, self.count - 1));
+        let value = seed.deserialize(&mut *self.de)?;
+        self.de.path.pop();
 
         if self.count < self.len {
             // if there are still elements in the sequence, seek back to the next
             // items's head
+            let new_offset = self.de.head_offset()?;
             self.de.seek(SeekFrom::Start(new_offset as u64))?;
         }
 
-        self.de.push_scope(scope);
-        res.map(|value| Some(value))
+        Ok(Some(value))
     }
 }
 
 struct DynamicTupleAccess<'r, 'a, R: 'r> {
     len: usize,
     count: usize,
+    fields: Option<&'static [&'static str]>,
+    /// Index of the tuple if it was guessed to be dynamic
+    tuple_index: Option<u64>,
+    /// Position of the scope of the tuple in the scope stack
+    depth: usize,
     de: &'a mut Deserializer<'r, R>,
 }
 
 impl<'r, 'a, R: Read + Seek + 'r> DynamicTupleAccess<'r, 'a, R> {
-    fn new(de: &'a mut Deserializer<'r, R>, len: usize) -> Self {
+    fn new(
+        de: &'a mut Deserializer<'r, R>,
+        len: usize,
+        fields: Option<&'static [&'static str]>,
+        tuple_index: Option<u64>,
+    ) -> Self {
         DynamicTupleAccess {
-            len: len,
+            len,
             count: 0,
-            de: de,
+            fields,
+            tuple_index,
+            depth: de.scope.len(),
+            de,
         }
     }
 
+    /// get_error hints that a tuple guessed to be dynamic is static
+    /// when decoding its elements fails and none of them is dynamic
     fn get_error(&mut self, error: Error) -> Error {
-        match error.classify() {
-            Category::Data => {
-                let has_dynamic_types = self
-                    .de
-                    .pop_scope()
-                    .map(|scope| {
-                        let has_dynamic_types = scope.has_dynamic_types();
-                        self.de.push_scope(scope);
-                        has_dynamic_types
-                    })
-                    .unwrap_or(false);
-
-                let should_give_hint = error.description() == "insufficient bytes read from reader"
-                    && !has_dynamic_types;
-
-                if should_give_hint {
-                    Error::hint(TupleHint::new(self.de.tuple_counter, false), error)
-                } else {
-                    error
-                }
-            }
-            _ => error,
+        let index = match self.tuple_index {
+            Some(index) if !error.is_hint() && error.kind() != ErrorKind::Io => index,
+            _ => return error,
+        };
+
+        // scopes are not removed on error, so the scope of the tuple
+        // may not be the last one
+        let has_dynamic_types = self
+            .de
+            .scope
+            .get(self.depth.wrapping_sub(1))
+            .map(|scope| scope.has_dynamic_types())
+            .unwrap_or(false);
+
+        if has_dynamic_types {
+            error
+        } else {
+            Error::hint(TupleHint::new(index, false), error)
         }
     }
 }
@@ -805,22 +1339,81 @@ impl<'de, 'r, 'a, R: Read + Seek + 'a> de::SeqAccess<'de> for DynamicTupleAccess
             return Ok(None);
         }
 
-        let res = seed.deserialize(&mut *self.de);
-        if let Err(error) = res {
-            return Err(self.get_error(error));
-        }
-
-        let scope = self.de.pop_scope().unwrap();
-        let new_offset = scope.offset + scope.read_head;
+        self.de
+            .path
+            .push(PathSegment::new(self.fields, self.count - 1));
+        let value = match seed.deserialize(&mut *self.de) {
+            Ok(value) => value,
+            Err(error) => return Err(self.get_error(error)),
+        };
+        self.de.path.pop();
 
         if self.count < self.len {
             // if there are still elements in the sequence, seek back to the next
             // items's head
+            let new_offset = self.de.head_offset()?;
             self.de.seek(SeekFrom::Start(new_offset as u64))?;
         }
 
-        self.de.push_scope(scope);
-        res.map(|value| Some(value))
+        Ok(Some(value))
+    }
+}
+
+/// relative_offset converts an offset read from the input, which is
+/// in bytes, to the offset in the hex encoding
+fn relative_offset(offset: u64) -> Result<u64> {
+    offset.checked_mul(2).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidOffset,
+            "offset of dynamic item is too large",
+        )
+    })
+}
+
+/// absolute_offset adds an offset relative to a scope to the offset
+/// at which the scope begins
+fn absolute_offset(scope_offset: u64, offset: u64) -> Result<u64> {
+    scope_offset.checked_add(offset).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidOffset,
+            "offset of dynamic item is too large",
+        )
+    })
+}
+
+/// non_canonical_offset returns the error for an offset that does not
+/// match the canonical encoding. If the offset belongs to a tuple that
+/// was guessed to be dynamic, the tuple is hinted to be static instead
+fn non_canonical_offset(tuple_index: Option<u64>) -> Error {
+    let error = Error::new(
+        ErrorKind::InvalidOffset,
+        "non canonical encoding: unexpected offset for dynamic item",
+    );
+    match tuple_index {
+        Some(index) => Error::hint(TupleHint::new(index, false), error),
+        None => error,
+    }
+}
+
+/// head_offset_error returns the error for an offset that points into
+/// the head of its scope, hinting that the tuple it belongs to is static
+fn head_offset_error(tuple_index: Option<u64>) -> Error {
+    let error = Error::new(
+        ErrorKind::InvalidOffset,
+        "offset of dynamic item points into the head",
+    );
+    match tuple_index {
+        Some(index) => Error::hint(TupleHint::new(index, false), error),
+        None => error,
+    }
+}
+
+/// hint_base_type returns the type a tuple is decoded as with a hint
+fn hint_base_type(hint: &TupleHint) -> BaseType {
+    if hint.is_dynamic {
+        BaseType::Dynamic
+    } else {
+        BaseType::Static
     }
 }
 
@@ -829,70 +1422,492 @@ pub struct RefReadSeek<R> {
 }
 
 impl<R: Read + Seek> RefReadSeek<R> {
+    pub fn new(read: R) -> Self {
+        RefReadSeek { read }
+    }
+
     fn read(&mut self, bytes: &mut [u8]) -> Result<usize> {
         self.read.read(bytes).map_err(Error::io)
     }
 
+    fn position(&mut self) -> Result<u64> {
+        self.read.stream_position().map_err(Error::io)
+    }
+
     fn seek(&mut self, offset: SeekFrom) -> Result<u64> {
         self.read.seek(offset).map_err(Error::io)
     }
+
+    /// len returns the length of the input, without moving from the
+    /// current position
+    fn len(&mut self) -> Result<u64> {
+        let pos = self.position()?;
+        let len = self.seek(SeekFrom::End(0))?;
+        self.seek(SeekFrom::Start(pos))?;
+        Ok(len)
+    }
+}
+
+/// Step is a step of the path from the value at the root of the input
+/// to a nested dynamic array. Every step follows the offset of a
+/// dynamic item, so all the containers along the path are dynamic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// Field of a tuple, by the index of its word in the head of the
+    /// tuple. It is the index of the field when all the fields before
+    /// it are dynamic or a single word
+    Field(usize),
+
+    /// Element of a dynamic array, by its index
+    Element(usize),
+}
+
+/// SeqIter decodes the elements of a dynamic array one at a time, so
+/// arrays with a lot of elements can be processed without holding all
+/// of them in memory. The array can be the value at the root of the
+/// input or nested in it, in which case it is found through a path of
+/// `Step`s.
+///
+/// Iteration stops after the first error.
+///
+/// ```
+/// use serde_eth::de::{RefReadSeek, SeqIter};
+/// use serde_eth::io::Cursor;
+///
+/// let encoded = serde_eth::to_string(&vec![1u64, 2, 3]).unwrap();
+/// let mut read = RefReadSeek::new(Cursor::new(encoded));
+/// let mut sum = 0;
+/// for value in SeqIter::<_, u64>::new(&mut read).unwrap() {
+///     sum += value.unwrap();
+/// }
+/// assert_eq!(sum, 6);
+/// ```
+pub struct SeqIter<'r, R, T> {
+    de: Deserializer<'r, R>,
+
+    /// Number of elements of the array
+    len: usize,
+
+    /// Number of elements decoded
+    count: usize,
+
+    /// Length of the head of the array, which is known once the
+    /// first element is decoded
+    head_len: usize,
+
+    /// Offset at which the next tail has to be in strict mode
+    expected_tail: usize,
+
+    /// Set when the array is the value at the root, so the input
+    /// has to end with it
+    root: bool,
+    done: bool,
+    marker: PhantomData<T>,
+}
+
+impl<'r, R: Read + Seek, T: de::DeserializeOwned> SeqIter<'r, R, T> {
+    /// new iterates over the elements of the dynamic array at the
+    /// root of the input
+    pub fn new(read: &'r mut RefReadSeek<R>) -> Result<Self> {
+        SeqIter::with_options(read, DeserializerOptions::default())
+    }
+
+    /// with_options iterates over the elements of the dynamic array at
+    /// the root of the input, with the limits set in `options`
+    pub fn with_options(
+        read: &'r mut RefReadSeek<R>,
+        options: DeserializerOptions,
+    ) -> Result<Self> {
+        SeqIter::nested(read, &[], options)
+    }
+
+    /// nested iterates over the elements of the dynamic array found by
+    /// following `path` from the value at the root of the input. With
+    /// the `params` option the root is the list of parameters, so the
+    /// path starts with the field of the parameter
+    pub fn nested(
+        read: &'r mut RefReadSeek<R>,
+        path: &[Step],
+        options: DeserializerOptions,
+    ) -> Result<Self> {
+        let mut iter = SeqIter {
+            de: Deserializer::with_options(read, options),
+            len: 0,
+            count: 0,
+            head_len: 0,
+            expected_tail: 0,
+            root: path.is_empty(),
+            done: false,
+            marker: PhantomData,
+        };
+
+        iter.enter(path).map_err(|err| iter.de.locate(err))?;
+        Ok(iter)
+    }
+
+    /// enter follows the path to the array and enters its scope
+    fn enter(&mut self, path: &[Step]) -> Result<()> {
+        // the encoding of a single value is the same as the one of a
+        // list of parameters with the value as the only parameter
+        let root = if self.de.options.params {
+            if path.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::Custom,
+                    "the list of parameters is not an array",
+                ));
+            }
+            None
+        } else {
+            Some(Step::Field(0))
+        };
+
+        // offset of the item the last step points to, which is the
+        // beginning of a tuple or the length of an array
+        let mut offset = 0;
+        for (index, step) in root.iter().chain(path.iter()).enumerate() {
+            // scope is where the container begins and word the index of
+            // the word with the offset in its head
+            let (scope, word) = match *step {
+                Step::Field(field) => (offset, field),
+                Step::Element(element) => {
+                    self.de.seek(SeekFrom::Start(offset))?;
+                    if element as u64 >= self.de.read_uint_head(64)? {
+                        return Err(Error::new(
+                            ErrorKind::InvalidLength,
+                            "array index in path is out of bounds",
+                        ));
+                    }
+                    (absolute_offset(offset, 64)?, element)
+                }
+            };
+
+            let head = (word as u64).checked_mul(64).ok_or_else(|| {
+                Error::new(ErrorKind::InvalidOffset, "index in path is too large")
+            })?;
+            self.de
+                .seek(SeekFrom::Start(absolute_offset(scope, head)?))?;
+            let item_offset = relative_offset(self.de.read_uint_head(64)?)?;
+            offset = absolute_offset(scope, item_offset)?;
+
+            // the offset before the value at the root is not in the path
+            if root.is_some() && index == 0 {
+                if self.de.options.strict && item_offset != 64 {
+                    return Err(non_canonical_offset(None));
+                }
+            } else {
+                self.de.path.push(PathSegment::Index(word));
+            }
+        }
+
+        self.de.seek(SeekFrom::Start(offset))?;
+        let len = self.de.read_uint_head(64)?;
+        if len > self.de.options.max_array_len {
+            return Err(Error::new(
+                ErrorKind::LimitExceeded,
+                "array length exceeds the maximum array length",
+            ));
+        }
+
+        self.len = usize::try_from(len).map_err(|_| {
+            Error::new(
+                ErrorKind::LimitExceeded,
+                "array length exceeds the maximum array length",
+            )
+        })?;
+        self.de.enter_scope(Scope::new(64 + offset as usize))
+    }
+
+    /// next_element decodes the next element. Like `from_reader_seed`
+    /// it attempts again with hints when guessing a tuple fails, but
+    /// only the element is decoded again
+    fn next_element(&mut self) -> Result<T> {
+        let depth = self.de.scope.len();
+        let path = self.de.path.len();
+        let (read_head, read_tail) = match self.de.scope.last() {
+            Some(scope) => (scope.read_head, scope.read_tail),
+            None => return Err(Error::new(ErrorKind::Custom, "array scope is missing")),
+        };
+        let remaining_size = self.de.remaining_size;
+        let tuple_counter = self.de.tuple_counter;
+        let static_tuples = self.de.static_tuples.clone();
+        let mut first_error = None;
+        let mut retries = 0;
+
+        self.de.tuple_hints.clear();
+        loop {
+            self.de.guessed_tuples.clear();
+            let err = match self.read_element() {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+
+            let hint = self.de.retry_hint(&err);
+            let err = self.de.locate(err);
+            let hint = match hint {
+                Some(hint) => hint,
+                None => return Err(first_error.unwrap_or(err)),
+            };
+
+            if !err.is_hint() && first_error.is_none() {
+                first_error = Some(err);
+            }
+
+            retries += 1;
+            self.de
+                .check_retries(retries)
+                .map_err(|err| self.de.locate(err))?;
+
+            // go back to the state before the element
+            self.de.scope.truncate(depth);
+            if let Some(scope) = self.de.scope.last_mut() {
+                scope.read_head = read_head;
+                scope.read_tail = read_tail;
+                scope.types.clear();
+                scope.tails.clear();
+            }
+            self.de.path.truncate(path);
+            self.de.remaining_size = remaining_size;
+            self.de.tuple_counter = tuple_counter;
+            self.de.static_tuples = static_tuples.clone();
+            self.de.current_custom_deserializer = None;
+            self.de.current_fields = None;
+            self.de
+                .tuple_hints
+                .insert(hint.index, hint_base_type(&hint));
+        }
+    }
+
+    fn read_element(&mut self) -> Result<T> {
+        let head = self.de.head_offset()?;
+        self.de.seek(SeekFrom::Start(head as u64))?;
+
+        self.de.path.push(PathSegment::Element(self.count));
+        let value = T::deserialize(&mut self.de)?;
+        self.de.path.pop();
+
+        let scope = self
+            .de
+            .scope
+            .last_mut()
+            .ok_or_else(|| Error::new(ErrorKind::Custom, "array scope is missing"))?;
+
+        // all the elements have a head of the same length
+        if self.count == 0 {
+            self.head_len = scope.read_head.checked_mul(self.len).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::LimitExceeded,
+                    "array length exceeds the maximum array length",
+                )
+            })?;
+            self.expected_tail = self.head_len;
+        }
+
+        // the scope keeps track of the types and tails of the elements
+        // until it exits, but here they are checked one element at a
+        // time to use constant memory
+        scope.types.clear();
+        for tail in scope.tails.drain(..) {
+            if self.de.options.strict {
+                if tail.offset != self.expected_tail {
+                    return Err(non_canonical_offset(tail.tuple_index));
+                }
+                self.expected_tail += tail.size;
+            } else if tail.offset < self.head_len {
+                return Err(head_offset_error(tail.tuple_index));
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// finish exits the scope of the array once all of its elements
+    /// are decoded
+    fn finish(&mut self) -> Result<()> {
+        let scope = self.de.exit_scope()?;
+        if self.de.options.strict && scope.read_head != self.head_len {
+            return Err(non_canonical_offset(None));
+        }
+
+        if self.root {
+            self.de.end()?;
+        }
+        Ok(())
+    }
+}
+
+impl<'r, R: Read + Seek, T: de::DeserializeOwned> Iterator for SeqIter<'r, R, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        if self.done {
+            return None;
+        }
+
+        if self.count == self.len {
+            self.done = true;
+            return match self.finish() {
+                Ok(()) => None,
+                Err(err) => Some(Err(self.de.locate(err))),
+            };
+        }
+
+        match self.next_element() {
+            Ok(value) => {
+                self.count += 1;
+                Some(Ok(value))
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
 }
 
 pub fn from_reader<'de, R: Read + Seek, T: de::Deserialize<'de>>(read: R) -> Result<T> {
-    let mut hints = HashMap::new();
-    let mut read = RefReadSeek { read: read };
+    from_reader_with_options(read, DeserializerOptions::default())
+}
+
+pub fn from_reader_with_options<'de, R: Read + Seek, T: de::Deserialize<'de>>(
+    read: R,
+    options: DeserializerOptions,
+) -> Result<T> {
+    from_reader_seed(read, PhantomData, options)
+}
+
+/// from_reader_seed deserializes a value using a `DeserializeSeed`, which
+/// is useful when the type of the value is only known at runtime. The
+/// seed is cloned because the input may be read more than once to find
+/// out which tuples are static
+pub fn from_reader_seed<'de, R: Read + Seek, S: de::DeserializeSeed<'de> + Clone>(
+    read: R,
+    seed: S,
+    options: DeserializerOptions,
+) -> Result<S::Value> {
+    let mut hints = BTreeMap::new();
+    let mut first_error = None;
+    let mut retries = 0;
+    let mut read = RefReadSeek::new(read);
 
     loop {
         let mut de = Deserializer::with_props(
             &mut read,
             DeserializerProperties {
                 tuple_hints: hints.clone(),
-                max_size: 0,
+                options: options.clone(),
             },
         );
-        let res = de::Deserialize::deserialize(&mut de);
-        match res {
-            Err(err) => {
-                let hint = err.tuple_hint();
-                if hint.is_none() {
-                    return Err(err);
-                }
-
-                let hint = hint.unwrap();
-                if hints.contains_key(&hint.index) {
-                    return Err(err);
-                }
+        let err = match seed.clone().deserialize(&mut de) {
+            Ok(value) => match de.end() {
+                Ok(()) => return Ok(value),
+                Err(err) => err,
+            },
+            Err(err) => err,
+        };
 
-                hints.insert(
-                    hint.index,
-                    if hint.is_dynamic {
-                        BaseType::Dynamic
-                    } else {
-                        BaseType::Static
-                    },
-                );
+        let hint = de.retry_hint(&err);
+        let err = de.locate(err);
+        let hint = match hint {
+            Some(hint) => hint,
+            // if guessing did not help, the error of the first attempt
+            // is the most accurate one
+            None => return Err(first_error.unwrap_or(err)),
+        };
 
-                read.seek(SeekFrom::Start(0))?;
-            }
-            _ => {
-                de.end()?;
-                return res;
-            }
+        if !err.is_hint() && first_error.is_none() {
+            first_error = Some(err);
         }
+
+        retries += 1;
+        de.check_retries(retries).map_err(|err| de.locate(err))?;
+
+        hints.insert(hint.index, hint_base_type(&hint));
+        read.seek(SeekFrom::Start(0))?;
     }
 }
 
+/// from_async_reader deserializes a value from an asynchronous source.
+/// Offsets may point anywhere in the input, so it is buffered first and
+/// then decoded like `from_reader` does
+#[cfg(feature = "async")]
+pub async fn from_async_reader<R, T>(read: R) -> Result<T>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+    T: de::DeserializeOwned,
+{
+    from_async_reader_with_options(read, DeserializerOptions::default()).await
+}
+
+/// from_async_reader_with_options deserializes a value from an asynchronous
+/// source with the given options. At most one byte more than `max_size` is
+/// buffered, since the deserializer never reads past that
+#[cfg(feature = "async")]
+pub async fn from_async_reader_with_options<R, T>(
+    mut read: R,
+    options: DeserializerOptions,
+) -> Result<T>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+    T: de::DeserializeOwned,
+{
+    let len = read.seek(SeekFrom::End(0)).await.map_err(Error::io)?;
+    let limit = len.min(options.max_size.saturating_add(1));
+    read.seek(SeekFrom::Start(0)).await.map_err(Error::io)?;
+
+    let mut buf = Vec::with_capacity(usize::try_from(limit).unwrap_or(0));
+    read.take(limit)
+        .read_to_end(&mut buf)
+        .await
+        .map_err(Error::io)?;
+    from_reader_with_options(Cursor::new(buf), options)
+}
+
+/// from_slice deserializes a value from the bytes of its hex encoding
+pub fn from_slice<'a, T: de::Deserialize<'a>>(v: &'a [u8]) -> Result<T> {
+    from_reader(Cursor::new(v))
+}
+
 pub fn from_str<'a, T: de::Deserialize<'a>>(s: &'a str) -> Result<T> {
     from_reader(Cursor::new(s))
 }
 
-#[cfg(test)]
+pub fn from_str_with_options<'a, T: de::Deserialize<'a>>(
+    s: &'a str,
+    options: DeserializerOptions,
+) -> Result<T> {
+    from_reader_with_options(Cursor::new(s), options)
+}
+
+/// from_str_seed deserializes a value from a string using a `DeserializeSeed`
+pub fn from_str_seed<'a, S: de::DeserializeSeed<'a> + Clone>(
+    s: &'a str,
+    seed: S,
+) -> Result<S::Value> {
+    from_reader_seed(Cursor::new(s), seed, DeserializerOptions::default())
+}
+
+/// from_str_params deserializes a tuple from the list of parameters
+/// of a function call, as serialized by `to_string_params`
+pub fn from_str_params<'a, T: de::Deserialize<'a>>(s: &'a str) -> Result<T> {
+    from_str_with_options(s, DeserializerOptions::new().params(true))
+}
+
+// the tests compare the descriptions of errors, which need std
+#[cfg(all(test, feature = "std"))]
 mod tests {
 
-    use super::from_str;
-    use crate::{error::Result, serde_tests};
-    use serde::{de, ser};
-    use std::{error::Error, fmt::Debug};
+    use super::{
+        from_slice, from_str, from_str_params, from_str_with_options, DeserializerOptions,
+        RefReadSeek, SeqIter, Step,
+    };
+    use crate::{
+        error::{ErrorKind, Result},
+        ser::to_string,
+        serde_tests,
+    };
+    use oasis_std::types::{H256, U256};
+    use serde::{de, ser, Deserialize};
+    use std::{convert::TryFrom, error::Error, fmt::Debug, io::Cursor};
 
     fn test_parse_ok<T: Clone + Debug + PartialEq + ser::Serialize + de::DeserializeOwned>(
         tests: &[(T, &str)],
@@ -900,9 +1915,52 @@ mod tests {
         for (value, s) in tests {
             let v: T = from_str(s).unwrap();
             assert_eq!(v, value.clone());
+
+            let v: T = from_slice(s.as_bytes()).unwrap();
+            assert_eq!(v, value.clone());
+
+            // the test vectors are canonical encodings
+            let v: T = from_str_with_options(s, DeserializerOptions::new().strict(true)).unwrap();
+            assert_eq!(v, value.clone());
+        }
+    }
+
+    fn test_parse_params_ok<
+        T: Clone + Debug + PartialEq + ser::Serialize + de::DeserializeOwned,
+    >(
+        tests: &[(T, &str)],
+    ) {
+        for (value, s) in tests {
+            let v: T = from_str_params(s).unwrap();
+            assert_eq!(v, value.clone());
+
+            let options = DeserializerOptions::new().params(true).strict(true);
+            let v: T = from_str_with_options(s, options).unwrap();
+            assert_eq!(v, value.clone());
+        }
+    }
+
+    fn seq_iter<T: de::DeserializeOwned>(
+        s: &str,
+        path: &[Step],
+        options: DeserializerOptions,
+    ) -> Result<Vec<T>> {
+        let mut read = RefReadSeek::new(Cursor::new(s));
+        SeqIter::nested(&mut read, path, options)?.collect()
+    }
+
+    fn test_seq_iter_ok<T: Debug + PartialEq + de::DeserializeOwned>(tests: &[(Vec<T>, &str)]) {
+        for (value, s) in tests {
+            let v: Vec<T> = seq_iter(s, &[], DeserializerOptions::new()).unwrap();
+            assert_eq!(&v, value);
+
+            let options = DeserializerOptions::new().strict(true);
+            let v: Vec<T> = seq_iter(s, &[], options).unwrap();
+            assert_eq!(&v, value);
         }
     }
 
+    #[allow(deprecated)]
     fn test_parse_error<T: Clone + Debug + PartialEq + ser::Serialize + de::DeserializeOwned>(
         tests: &[(&str, &str)],
     ) {
@@ -948,6 +2006,11 @@ mod tests {
         test_parse_ok(&serde_tests::test_address()[..]);
     }
 
+    #[test]
+    fn test_parse_checksum_address() {
+        test_parse_ok(&serde_tests::test_checksum_address()[..]);
+    }
+
     #[test]
     fn test_parse_h256() {
         test_parse_ok(&serde_tests::test_h256()[..]);
@@ -1002,6 +2065,54 @@ mod tests {
         test_parse_error::<u64>(tests);
     }
 
+    /// test_parse_int_bounds decodes the values around the bounds of a
+    /// signed integer of the given width, sign extended and zero extended
+    /// to a word. Only the values in range, encoded sign extended, decode
+    fn test_parse_int_bounds<T>(width: u32)
+    where
+        T: Debug + PartialEq + TryFrom<i128> + de::DeserializeOwned,
+    {
+        let min = -(1i128 << (width - 1));
+        let max = (1i128 << (width - 1)) - 1;
+        let mask = (1u128 << width) - 1;
+
+        for value in [min, max, max + 1, min - 1] {
+            let sign_extended = if value < 0 {
+                format!("{}{:032x}", "f".repeat(32), value as u128)
+            } else {
+                format!("{:064x}", value)
+            };
+            let zero_extended = format!("{:064x}", value as u128 & mask);
+
+            for (s, expected) in [
+                (sign_extended, Some(value)),
+                (zero_extended, Some((value as u128 & mask) as i128)),
+            ] {
+                let expected = expected.and_then(|value| T::try_from(value).ok());
+                for options in [
+                    DeserializerOptions::new(),
+                    DeserializerOptions::new().strict(true),
+                ] {
+                    let res: Result<T> = from_str_with_options(&s, options);
+                    match &expected {
+                        Some(expected) => assert_eq!(res.unwrap(), *expected, "{}", s),
+                        None => {
+                            assert_eq!(res.unwrap_err().kind(), ErrorKind::IntegerOverflow, "{}", s)
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_int_bounds_all_widths() {
+        test_parse_int_bounds::<i8>(8);
+        test_parse_int_bounds::<i16>(16);
+        test_parse_int_bounds::<i32>(32);
+        test_parse_int_bounds::<i64>(64);
+    }
+
     #[test]
     fn test_parse_float_error() {
         let tests = &[
@@ -1233,6 +2344,11 @@ mod tests {
         test_parse_ok(&serde_tests::test_complex_struct()[..]);
     }
 
+    #[test]
+    fn test_parse_nested_struct() {
+        test_parse_ok(&serde_tests::test_nested_struct()[..]);
+    }
+
     #[test]
     fn test_parse_composed_struct() {
         test_parse_ok(&serde_tests::test_composed_struct()[..]);
@@ -1247,4 +2363,676 @@ mod tests {
     fn test_parse_reversed_composed_struct() {
         test_parse_ok(&serde_tests::test_reversed_composed_struct()[..]);
     }
+
+    #[test]
+    fn test_parse_spec() {
+        test_parse_params_ok(&serde_tests::test_spec_baz()[..]);
+        test_parse_params_ok(&serde_tests::test_spec_bar()[..]);
+        test_parse_params_ok(&serde_tests::test_spec_sam()[..]);
+        test_parse_params_ok(&serde_tests::test_spec_f()[..]);
+        test_parse_params_ok(&serde_tests::test_spec_g()[..]);
+    }
+
+    #[test]
+    fn test_seq_iter() {
+        test_seq_iter_ok(&serde_tests::test_seq_int()[..]);
+        test_seq_iter_ok(&serde_tests::test_str_seq()[..]);
+        test_seq_iter_ok(&serde_tests::test_multiseq()[..]);
+
+        // tuples are guessed one element at a time
+        let values = vec![(32u64, 1u8), (0, 2), (64, 3)];
+        let s = crate::to_string(&values).unwrap();
+        test_seq_iter_ok(&[(values, s.as_str())]);
+
+        let values = vec![
+            ("a".to_string(), (H256::from([1u8; 32]), [32u32; 4])),
+            (String::new(), (H256::zero(), [0u32; 4])),
+        ];
+        let s = crate::to_string(&values).unwrap();
+        test_seq_iter_ok(&[(values, s.as_str())]);
+    }
+
+    #[test]
+    fn test_seq_iter_nested() {
+        type Item = (String, (H256, [u32; 4]));
+
+        let s = serde_tests::test_nested_struct()[0].1;
+        let values: Vec<String> =
+            seq_iter(s, &[Step::Field(2)], DeserializerOptions::new()).unwrap();
+        assert_eq!(values, vec!["c".to_string()]);
+
+        let value = (
+            7u64,
+            vec![
+                vec![("a".to_string(), (H256::from([1u8; 32]), [2u32; 4]))],
+                vec![
+                    ("b".to_string(), (H256::from([3u8; 32]), [4u32; 4])),
+                    ("c".to_string(), (H256::from([5u8; 32]), [6u32; 4])),
+                ],
+            ],
+        );
+        let s = crate::to_string(&value).unwrap();
+        let path = [Step::Field(1), Step::Element(1)];
+        let values: Vec<Item> =
+            seq_iter(&s, &path, DeserializerOptions::new().strict(true)).unwrap();
+        assert_eq!(values, value.1[1]);
+
+        let path = [Step::Field(1), Step::Element(2)];
+        let res: Result<Vec<Item>> = seq_iter(&s, &path, DeserializerOptions::new());
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidLength);
+
+        let s = serde_tests::test_spec_sam()[0].1;
+        let options = DeserializerOptions::new().params(true).strict(true);
+        let values: Vec<U256> = seq_iter(s, &[Step::Field(2)], options).unwrap();
+        assert_eq!(values, vec![U256::from(1), U256::from(2), U256::from(3)]);
+    }
+
+    #[test]
+    fn test_seq_iter_error() {
+        let s = crate::to_string(&vec!["a".to_string(), "b".to_string()]).unwrap();
+
+        // iteration stops after an error
+        let trailing = format!("{}00", s);
+        let mut read = RefReadSeek::new(Cursor::new(trailing));
+        let mut iter = SeqIter::<_, String>::new(&mut read).unwrap();
+        assert_eq!(iter.next().unwrap().unwrap(), "a");
+        assert_eq!(iter.next().unwrap().unwrap(), "b");
+        assert_eq!(
+            iter.next().unwrap().unwrap_err().kind(),
+            ErrorKind::TrailingData
+        );
+        assert!(iter.next().is_none());
+
+        // elements are decoded lazily, so an invalid element is only
+        // found when reached
+        let invalid = format!(
+            "{}{}{}",
+            &s[..s.len() - 128],
+            "f".repeat(64),
+            &s[s.len() - 64..]
+        );
+        let mut read = RefReadSeek::new(Cursor::new(invalid));
+        let mut iter = SeqIter::<_, String>::new(&mut read).unwrap();
+        assert_eq!(iter.next().unwrap().unwrap(), "a");
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+    }
+
+    #[cfg(feature = "async")]
+    fn test_parse_async_ok<T: Debug + PartialEq + de::DeserializeOwned>(tests: &[(T, &str)]) {
+        for (value, s) in tests {
+            let read = futures_util::io::Cursor::new(s.as_bytes());
+            let v: T = futures_executor::block_on(super::from_async_reader(read)).unwrap();
+            assert_eq!(&v, value);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn test_parse_async() {
+        test_parse_async_ok(&serde_tests::test_u256()[..]);
+        test_parse_async_ok(&serde_tests::test_string()[..]);
+        test_parse_async_ok(&serde_tests::test_multiseq()[..]);
+        test_parse_async_ok(&serde_tests::test_nested_struct()[..]);
+        test_parse_async_ok(&serde_tests::test_composed_struct()[..]);
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn test_parse_async_options() {
+        use super::from_async_reader_with_options;
+        use futures_executor::block_on;
+        use futures_util::io::Cursor;
+
+        let s = "0000000000000000000000000000000000000000000000000000000000000020\
+                 0000000000000000000000000000000000000000000000000000000000000001\
+                 0000000000000000000000000000000000000000000000000000000000000020\
+                 0000000000000000000000000000000000000000000000000000000000000005\
+                 68656c6c6f000000000000000000000000000000000000000000000000000000";
+
+        let options = DeserializerOptions::new().max_size(128);
+        let res: Result<Vec<String>> =
+            block_on(from_async_reader_with_options(Cursor::new(s), options));
+        assert!(res.unwrap_err().is_limit());
+
+        // the input past max_size is not buffered, except for the byte
+        // that shows that there is trailing data
+        let long = format!("{}{}", s, "0".repeat(1 << 16));
+        let options = DeserializerOptions::new().max_size(320);
+        let v: Vec<String> = block_on(from_async_reader_with_options(
+            Cursor::new(s),
+            options.clone(),
+        ))
+        .unwrap();
+        assert_eq!(v, vec!["hello".to_string()]);
+        let res: Result<Vec<String>> = block_on(from_async_reader_with_options(
+            Cursor::new(&long),
+            options.clone(),
+        ));
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::TrailingData);
+        let res: Result<Vec<String>> = from_str_with_options(&long, options);
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::TrailingData);
+
+        let options = DeserializerOptions::new().max_size(384);
+        let res: Result<Vec<String>> =
+            block_on(from_async_reader_with_options(Cursor::new(&long), options));
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::TrailingData);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_parse_options_error() {
+        let s = "0000000000000000000000000000000000000000000000000000000000000020\
+                 0000000000000000000000000000000000000000000000000000000000000001\
+                 0000000000000000000000000000000000000000000000000000000000000020\
+                 0000000000000000000000000000000000000000000000000000000000000005\
+                 68656c6c6f000000000000000000000000000000000000000000000000000000";
+
+        let tests = &[
+            (
+                DeserializerOptions::new().max_size(128),
+                "deserializer does not have enough space to allocate a vector in the stack",
+            ),
+            (
+                DeserializerOptions::new().max_alloc(4),
+                "dynamic item exceeds the maximum allocation size",
+            ),
+            (
+                DeserializerOptions::new().max_depth(1),
+                "maximum nesting depth exceeded",
+            ),
+            (
+                DeserializerOptions::new().max_array_len(0),
+                "array length exceeds the maximum array length",
+            ),
+        ];
+
+        for (options, expected) in tests {
+            let res: Result<Vec<String>> = from_str_with_options(s, options.clone());
+            assert_eq!(res.unwrap_err().description(), *expected);
+        }
+
+        let options = DeserializerOptions::new()
+            .max_size(320)
+            .max_alloc(5)
+            .max_depth(2)
+            .max_array_len(1);
+        let v: Vec<String> = from_str_with_options(s, options).unwrap();
+        assert_eq!(v, vec!["hello".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_retries() {
+        type Words = (u64, u64, u64, u64);
+
+        // words that are multiples of 32 but point past the end of the
+        // input are not offsets, so no tuple is guessed to be dynamic
+        let value: Vec<Words> = vec![(0x7fff_ffe0, 0, 0, 0); 256];
+        let s = to_string(&value).unwrap();
+        let options = DeserializerOptions::new().max_retries(0);
+        assert_eq!(
+            from_str_with_options::<Vec<Words>>(&s, options).unwrap(),
+            value
+        );
+
+        // every word points to the last word of the input, so the first
+        // tuple is guessed to be dynamic, and decoding it as static
+        // needs a retry
+        let n = 128;
+        let word = 128 * n as u64 - 32;
+        let value: Vec<Words> = vec![(word, word, word, word); n];
+        let s = to_string(&value).unwrap();
+
+        let options = DeserializerOptions::new().max_retries(0);
+        let res: Result<Vec<Words>> = from_str_with_options(&s, options);
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::LimitExceeded);
+        let options = DeserializerOptions::new().max_retries(1);
+        assert_eq!(
+            from_str_with_options::<Vec<Words>>(&s, options).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn test_parse_static_tuples_like_offsets() {
+        // the first field of every tuple looks like an offset into the
+        // input, but once a tuple is proven to be static the ones of
+        // the other elements are not guessed again
+        let value: Vec<(u64, u64)> = (0..200).map(|i| (0x2000, i)).collect();
+        let s = to_string(&value).unwrap();
+        assert_eq!(from_str::<Vec<(u64, u64)>>(&s).unwrap(), value);
+
+        let nested: Vec<Vec<(u64, u64)>> = vec![value.clone(); 100];
+        let s = to_string(&nested).unwrap();
+        assert_eq!(from_str::<Vec<Vec<(u64, u64)>>>(&s).unwrap(), nested);
+
+        let s = to_string(&value).unwrap();
+        let mut read = RefReadSeek::new(Cursor::new(&s));
+        let decoded = SeqIter::<_, (u64, u64)>::new(&mut read)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_parse_strict_error() {
+        let tests: &[(&str, Option<Vec<String>>, &str)] = &[
+            (
+                // tails in reverse order
+                "0000000000000000000000000000000000000000000000000000000000000020\
+                 0000000000000000000000000000000000000000000000000000000000000002\
+                 0000000000000000000000000000000000000000000000000000000000000080\
+                 0000000000000000000000000000000000000000000000000000000000000040\
+                 0000000000000000000000000000000000000000000000000000000000000001\
+                 6200000000000000000000000000000000000000000000000000000000000000\
+                 0000000000000000000000000000000000000000000000000000000000000001\
+                 6100000000000000000000000000000000000000000000000000000000000000",
+                None,
+                "non canonical encoding: unexpected offset for dynamic item",
+            ),
+            (
+                // both offsets point to the same tail
+                "0000000000000000000000000000000000000000000000000000000000000020\
+                 0000000000000000000000000000000000000000000000000000000000000002\
+                 0000000000000000000000000000000000000000000000000000000000000040\
+                 0000000000000000000000000000000000000000000000000000000000000040\
+                 0000000000000000000000000000000000000000000000000000000000000001\
+                 6100000000000000000000000000000000000000000000000000000000000000",
+                Some(vec!["a".to_string(), "a".to_string()]),
+                "non canonical encoding: unexpected offset for dynamic item",
+            ),
+            (
+                // gap between the head and the tail
+                "0000000000000000000000000000000000000000000000000000000000000020\
+                 0000000000000000000000000000000000000000000000000000000000000001\
+                 0000000000000000000000000000000000000000000000000000000000000040\
+                 0000000000000000000000000000000000000000000000000000000000000000\
+                 0000000000000000000000000000000000000000000000000000000000000001\
+                 6100000000000000000000000000000000000000000000000000000000000000",
+                Some(vec!["a".to_string()]),
+                "non canonical encoding: unexpected offset for dynamic item",
+            ),
+            (
+                // gap after the top level offset
+                "0000000000000000000000000000000000000000000000000000000000000040\
+                 0000000000000000000000000000000000000000000000000000000000000000\
+                 0000000000000000000000000000000000000000000000000000000000000000",
+                Some(vec![]),
+                "non canonical encoding: unexpected offset for dynamic item",
+            ),
+            (
+                // non zero padding
+                "0000000000000000000000000000000000000000000000000000000000000020\
+                 0000000000000000000000000000000000000000000000000000000000000001\
+                 0000000000000000000000000000000000000000000000000000000000000020\
+                 0000000000000000000000000000000000000000000000000000000000000001\
+                 6100000000000000000000000000000000000000000000000000000000000001",
+                Some(vec!["a".to_string()]),
+                "non canonical encoding: padding bytes must be zero",
+            ),
+            (
+                // uppercase hex digits
+                "0000000000000000000000000000000000000000000000000000000000000020\
+                 0000000000000000000000000000000000000000000000000000000000000001\
+                 0000000000000000000000000000000000000000000000000000000000000020\
+                 0000000000000000000000000000000000000000000000000000000000000001\
+                 4A00000000000000000000000000000000000000000000000000000000000000",
+                Some(vec!["J".to_string()]),
+                "non canonical encoding: hex digits must be lowercase",
+            ),
+        ];
+
+        for (s, value, expected) in tests {
+            // inputs that are not canonical may still be accepted
+            // when not in strict mode
+            if let Some(value) = value {
+                let v: Vec<String> = from_str(s).unwrap();
+                assert_eq!(&v, value);
+            }
+
+            let options = DeserializerOptions::new().strict(true);
+            let res: Result<Vec<String>> = from_str_with_options(s, options);
+            assert_eq!(res.unwrap_err().description(), *expected);
+        }
+
+        // an address with non zero bytes before the 20 address bytes
+        let s = "ffffffffffffffffffffffff5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+        assert!(from_str::<crate::Address>(s).is_ok());
+        let options = DeserializerOptions::new().strict(true);
+        let res: Result<crate::Address> = from_str_with_options(s, options);
+        assert_eq!(
+            res.unwrap_err().description(),
+            "non canonical encoding: padding bytes must be zero"
+        );
+    }
+
+    #[test]
+    fn test_parse_strict_trailing_data_at_max_size() {
+        let s = to_string(&vec!["hello".to_string()]).unwrap();
+        let options = DeserializerOptions::new()
+            .strict(true)
+            .max_size(s.len() as u64);
+        let v: Vec<String> = from_str_with_options(&s, options.clone()).unwrap();
+        assert_eq!(v, vec!["hello".to_string()]);
+
+        // the value uses up max_size, and the probe for trailing data
+        // must not be mistaken for the end of the input
+        for trailing in &["0", "00", &"0".repeat(64)] {
+            let long = format!("{}{}", s, trailing);
+            let res: Result<Vec<String>> = from_str_with_options(&long, options.clone());
+            assert_eq!(res.unwrap_err().kind(), ErrorKind::TrailingData);
+        }
+    }
+
+    #[test]
+    fn test_parse_strict_tuple_hint() {
+        // the first element of the first tuple looks like an offset, so
+        // it is first decoded as a dynamic tuple. Strict mode detects that
+        // the offsets are not canonical and decodes it as a static tuple
+        let s = "0000000000000000000000000000000000000000000000000000000000000020\
+                 0000000000000000000000000000000000000000000000000000000000000002\
+                 0000000000000000000000000000000000000000000000000000000000000040\
+                 0000000000000000000000000000000000000000000000000000000000000000\
+                 0000000000000000000000000000000000000000000000000000000000000000\
+                 0000000000000000000000000000000000000000000000000000000000000000";
+
+        let options = DeserializerOptions::new().strict(true);
+        let v: Vec<(u64, u64)> = from_str_with_options(s, options).unwrap();
+        assert_eq!(v, vec![(64, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn test_parse_error_position() {
+        // the nested struct test vector with invalid utf8 in the
+        // first string of `values`
+        let s = "0000000000000000000000000000000000000000000000000000000000000020\
+                 0000000000000000000000000000000000000000000000000000000000000007\
+                 0000000000000000000000000000000000000000000000000000000000000060\
+                 0000000000000000000000000000000000000000000000000000000000000120\
+                 0000000000000000000000000000000000000000000000000000000000000040\
+                 0000000000000000000000000000000000000000000000000000000000000080\
+                 0000000000000000000000000000000000000000000000000000000000000001\
+                 6100000000000000000000000000000000000000000000000000000000000000\
+                 0000000000000000000000000000000000000000000000000000000000000001\
+                 6200000000000000000000000000000000000000000000000000000000000000\
+                 0000000000000000000000000000000000000000000000000000000000000001\
+                 0000000000000000000000000000000000000000000000000000000000000020\
+                 0000000000000000000000000000000000000000000000000000000000000001\
+                 ff00000000000000000000000000000000000000000000000000000000000000";
+
+        let err = from_str::<serde_tests::Nested>(s).unwrap_err();
+        assert_eq!(err.path(), Some(".values[0]"));
+        assert_eq!(err.offset(), Some(832));
+        assert_eq!(err.scopes(), &[64, 704]);
+        assert_eq!(
+            err.to_string(),
+            "parsed byte array cannot decode to a char at .values[0] \
+             (offset 832, scopes at 64, 704)"
+        );
+
+        // trailing data is reported where it begins
+        let err = from_str::<u8>(
+            "0000000000000000000000000000000000000000000000000000000000000001\
+             00",
+        )
+        .unwrap_err();
+        assert_eq!(err.path(), Some(""));
+        assert_eq!(err.offset(), Some(64));
+        assert_eq!(
+            err.to_string(),
+            "input has not been processed completely (offset 64)"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_kind() {
+        fn kind<T: de::DeserializeOwned>(s: &str) -> ErrorKind {
+            from_str::<T>(s).map(|_| ()).unwrap_err().kind()
+        }
+
+        let word = |n: u64| format!("{:064x}", n);
+
+        assert_eq!(kind::<u8>(&word(256)), ErrorKind::IntegerOverflow);
+        assert_eq!(kind::<bool>(&word(2)), ErrorKind::InvalidBool);
+        assert_eq!(kind::<u64>(&"z".repeat(64)), ErrorKind::InvalidHex);
+        assert_eq!(kind::<u64>(&word(1)[..32]), ErrorKind::UnexpectedEof);
+        assert_eq!(
+            kind::<u8>(&format!("{}00", word(1))),
+            ErrorKind::TrailingData
+        );
+        assert_eq!(
+            kind::<String>(&format!("{}{}ff{}", word(32), word(1), "0".repeat(62))),
+            ErrorKind::InvalidUtf8
+        );
+        assert_eq!(
+            kind::<Option<u8>>(&format!("{}{}{}{}", word(32), word(2), word(1), word(1))),
+            ErrorKind::InvalidLength
+        );
+        assert_eq!(
+            kind::<f32>(&word(1)),
+            ErrorKind::Unsupported { what: "f32" }
+        );
+
+        let err = from_str::<u64>(&word(1)[..32]).unwrap_err();
+        assert!(err.is_eof());
+
+        let options = DeserializerOptions::new().max_depth(1);
+        let err = from_str_with_options::<Vec<u8>>(
+            &format!("{}{}{}", word(32), word(1), word(5)),
+            options,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::LimitExceeded);
+        assert!(err.is_limit());
+
+        let options = DeserializerOptions::new().strict(true);
+        let err = from_str_with_options::<Vec<u8>>(
+            &format!("{}{}{}", word(64), word(0), word(0)),
+            options,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidOffset);
+    }
+
+    #[test]
+    fn test_parse_fixed_shape_error() {
+        // types named like the fixed size types but with a different
+        // shape return an error instead of panicking
+        #[allow(dead_code)]
+        mod fixed {
+            use serde::Deserialize;
+
+            #[derive(Deserialize, Debug)]
+            pub struct U256(pub [u8; 32]);
+
+            #[derive(Deserialize, Debug)]
+            pub struct H256(pub [u64; 4]);
+
+            #[derive(Deserialize, Debug)]
+            pub struct H160(pub [u8; 24]);
+        }
+
+        let s = "0000000000000000000000000000000000000000000000000000000000000001";
+        assert_eq!(
+            from_str::<fixed::U256>(s).unwrap_err().kind(),
+            ErrorKind::Unsupported { what: "u8" }
+        );
+        assert_eq!(
+            from_str::<fixed::H256>(s).unwrap_err().kind(),
+            ErrorKind::Unsupported { what: "u64" }
+        );
+        assert_eq!(
+            from_str::<fixed::H160>(s).unwrap_err().kind(),
+            ErrorKind::InvalidLength
+        );
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(transparent)]
+    struct Recursive(Vec<Recursive>);
+
+    /// Rng is a xorshift generator so that the random inputs are the
+    /// same on every run
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+    }
+
+    /// mutate changes the encoding in a way that is likely to hit
+    /// edge cases in the deserializer, like huge offsets and lengths
+    fn mutate(rng: &mut Rng, s: &str) -> String {
+        const WORDS: &[&str] = &[
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "0000000000000000000000000000000000000000000000007fffffffffffffff",
+            "0000000000000000000000000000000000000000000000008000000000000000",
+            "000000000000000000000000000000000000000000000000ffffffffffffffe0",
+            "000000000000000000000000000000000000000000000000ffffffffffffffff",
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        ];
+
+        let mut s = s.to_string();
+
+        for _ in 0..=rng.below(3) {
+            let words = s.len() / 64;
+            match rng.below(6) {
+                0 if !s.is_empty() => {
+                    let i = rng.below(s.len());
+                    let c = b"0123456789abcdefAFx "[rng.below(20)] as char;
+                    s.replace_range(i..=i, &c.to_string());
+                }
+                1 if words > 0 => {
+                    let i = rng.below(words) * 64;
+                    s.replace_range(i..i + 64, WORDS[rng.below(WORDS.len())]);
+                }
+                2 => {
+                    let i = rng.below(s.len() + 1);
+                    s.truncate(i);
+                }
+                3 => {
+                    let i = rng.below(words + 1) * 64;
+                    s.insert_str(i, WORDS[rng.below(WORDS.len())]);
+                }
+                4 if words > 0 => {
+                    let i = rng.below(words) * 64;
+                    s.replace_range(i..i + 64, "");
+                }
+                _ => {
+                    let len = rng.below(256);
+                    s = (0..len)
+                        .map(|_| b"0123456789abcdef"[rng.below(16)] as char)
+                        .collect();
+                }
+            }
+        }
+
+        s
+    }
+
+    macro_rules! decode_all {
+        ( $s: expr, $options: expr, $($t: ty),+ ) => {
+            $(
+                let _ = from_str_with_options::<$t>($s, $options.clone());
+            )*
+        }
+    }
+
+    #[test]
+    fn test_parse_random_input() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        let option_sets = [
+            DeserializerOptions::new(),
+            DeserializerOptions::new().strict(true),
+            DeserializerOptions::new()
+                .max_size(512)
+                .max_alloc(16)
+                .max_depth(4)
+                .max_array_len(4),
+        ];
+
+        for encoding in serde_tests::test_encodings() {
+            for _ in 0..32 {
+                let s = mutate(&mut rng, encoding);
+                let options = &option_sets[rng.below(option_sets.len())];
+
+                let res = std::panic::catch_unwind(|| {
+                    decode_all!(
+                        &s,
+                        options,
+                        bool,
+                        u8,
+                        i8,
+                        i64,
+                        u64,
+                        char,
+                        String,
+                        Option<String>,
+                        Vec<u8>,
+                        Vec<Vec<String>>,
+                        (u8, String),
+                        [u8; 3],
+                        oasis_std::types::H160,
+                        oasis_std::types::H256,
+                        oasis_std::types::U256,
+                        crate::Address,
+                        serde_tests::Complex,
+                        serde_tests::Nested,
+                        serde_tests::Composed,
+                        serde_tests::ReversedComposed,
+                        Recursive
+                    );
+                });
+
+                assert!(res.is_ok(), "decoding {:?} panicked", s);
+            }
+        }
+    }
+
+    fn nested_arrays(depth: usize) -> String {
+        // every level is an array with a single element whose
+        // offset points right after the array length
+        let mut s = String::new();
+        s.push_str(&format!("{:064x}", 0x20));
+        for _ in 0..depth {
+            s.push_str(&format!("{:064x}{:064x}", 1, 0x20));
+        }
+        s.push_str(&format!("{:064x}", 0));
+        s
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_parse_max_depth() {
+        let v: Recursive = from_str(&nested_arrays(8)).unwrap();
+        let mut depth = 0;
+        let mut node = &v;
+        while let Some(child) = node.0.first() {
+            node = child;
+            depth += 1;
+        }
+        assert_eq!(depth, 8);
+
+        let err = from_str::<Recursive>(&nested_arrays(8192)).unwrap_err();
+        assert!(err.is_limit());
+        assert_eq!(err.description(), "maximum nesting depth exceeded");
+        assert_eq!(err.scopes().len(), 128);
+
+        let options = DeserializerOptions::new().max_depth(4);
+        let err = from_str_with_options::<Recursive>(&nested_arrays(8), options).unwrap_err();
+        assert!(err.is_limit());
+    }
 }
@@ -0,0 +1,284 @@
+//! Structured diffs of encodings, which find where two encodings of the
+//! same type first differ, e.g. an encoding made by the serializer and
+//! one made by solc.
+//!
+//! ```ignore
+//! if let Some(difference) = serde_eth::diff::diff(&value, &expected)? {
+//!     panic!("{}", difference);
+//! }
+//! let difference = serde_eth::diff::diff_str(&AbiType::parse("(uint256,string)")?, &a, &b)?;
+//! ```
+//!
+//! Both encodings are walked against the type like a dump walks them,
+//! and the difference is the first word whose annotation or content is
+//! not the same, with the path of the value it belongs to and its byte
+//! offset in each encoding, abbreviated here:
+//!
+//! ```text
+//! .simple.value1 at 0x80 (left) and 0x80 (right): length = 1 (00..01) != length = 2 (00..02)
+//! ```
+
+use alloc::string::{String, ToString};
+use core::{cmp, fmt};
+
+use serde::ser::Serialize;
+
+use super::{
+    abi_type::AbiType,
+    dump::{chunk, shape_of, walk, Entry, Shape},
+    error::{Error, Result},
+    eth,
+    ser::{to_string, to_string_params},
+};
+
+/// Difference is where two encodings first differ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    /// path is the path to the field or element that differs, e.g.
+    /// `.field[3].inner`. It is empty for the value at the root, and for
+    /// words that no value refers to
+    pub path: String,
+
+    /// left_offset is the byte offset of the word that differs in the
+    /// left encoding, if it has one there
+    pub left_offset: Option<usize>,
+
+    /// right_offset is the byte offset of the word that differs in the
+    /// right encoding, if it has one there
+    pub right_offset: Option<usize>,
+
+    /// left is what the word means in the left encoding
+    pub left: String,
+
+    /// right is what the word means in the right encoding
+    pub right: String,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let offset = |offset: Option<usize>| match offset {
+            Some(offset) => format!("{:#04x}", offset),
+            None => "nothing".to_string(),
+        };
+        let path = if self.path.is_empty() {
+            "value"
+        } else {
+            &self.path
+        };
+        write!(
+            f,
+            "{} at {} (left) and {} (right): {} != {}",
+            path,
+            offset(self.left_offset),
+            offset(self.right_offset),
+            self.left,
+            self.right
+        )
+    }
+}
+
+/// describe returns the annotation of an entry with the word it is about
+fn describe(data: &[u8], entry: Option<&Entry>) -> String {
+    match entry {
+        Some(entry) => format!("{} ({})", entry.note, chunk(data, entry.pos)),
+        None => "nothing".to_string(),
+    }
+}
+
+/// diff_shape compares two encodings of a value of the given shape. In
+/// params mode a tuple at the root is the list of parameters of a
+/// function call
+fn diff_shape(shape: Shape, left: &str, right: &str, params: bool) -> Result<Option<Difference>> {
+    let left = hex::decode(left).map_err(Error::hex_parsing)?;
+    let right = hex::decode(right).map_err(Error::hex_parsing)?;
+    let left_entries = walk(&shape, &left, params);
+    let right_entries = walk(&shape, &right, params);
+
+    for i in 0.. {
+        let (l, r) = (left_entries.get(i), right_entries.get(i));
+        let path = match (l, r) {
+            (None, None) => break,
+            (Some(l), Some(r))
+                if l.path == r.path
+                    && l.note == r.note
+                    && chunk(&left, l.pos) == chunk(&right, r.pos) =>
+            {
+                continue
+            }
+            (Some(entry), _) | (None, Some(entry)) => entry.path.clone(),
+        };
+
+        return Ok(Some(Difference {
+            path,
+            left_offset: l.map(|entry| entry.pos),
+            right_offset: r.map(|entry| entry.pos),
+            left: describe(&left, l),
+            right: describe(&right, r),
+        }));
+    }
+
+    // The values are the same, but the encodings may still differ in the
+    // words no value refers to
+    let len = eth::padded_len(cmp::max(left.len(), right.len()));
+    let difference = (0..len)
+        .step_by(32)
+        .find(|pos| chunk(&left, *pos) != chunk(&right, *pos))
+        .map(|pos| Difference {
+            path: String::new(),
+            left_offset: Some(pos).filter(|pos| *pos < left.len()),
+            right_offset: Some(pos).filter(|pos| *pos < right.len()),
+            left: chunk(&left, pos),
+            right: chunk(&right, pos),
+        });
+    Ok(difference)
+}
+
+/// diff encodes a value like `to_string` does and compares the encoding
+/// with `expected`, with the names of the fields of structs. The encoding
+/// is on the left
+pub fn diff<T: ?Sized + Serialize>(value: &T, expected: &str) -> Result<Option<Difference>> {
+    diff_shape(shape_of(value)?, &to_string(value)?, expected, false)
+}
+
+/// diff_params encodes a value like `to_string_params` does and compares
+/// the encoding with `expected`
+pub fn diff_params<T: ?Sized + Serialize>(value: &T, expected: &str) -> Result<Option<Difference>> {
+    diff_shape(shape_of(value)?, &to_string_params(value)?, expected, true)
+}
+
+/// diff_str compares two encodings of a value of the given type, as
+/// encoded by `to_string`
+pub fn diff_str(kind: &AbiType, left: &str, right: &str) -> Result<Option<Difference>> {
+    diff_shape(Shape::from_type(kind), left, right, false)
+}
+
+/// diff_str_params compares two encodings of the list of parameters of a
+/// function call
+pub fn diff_str_params(kinds: &[AbiType], left: &str, right: &str) -> Result<Option<Difference>> {
+    diff_shape(
+        Shape::from_type(&AbiType::Tuple(kinds.to_vec())),
+        left,
+        right,
+        true,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{diff, diff_params, diff_str, diff_str_params, Difference};
+    use crate::{
+        abi_type::AbiType,
+        ser::{to_string, to_string_params},
+    };
+    use alloc::string::{String, ToString};
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Simple {
+        value1: String,
+    }
+
+    #[derive(Serialize)]
+    struct Nested {
+        value: u64,
+        simple: Simple,
+    }
+
+    fn nested(value1: &str) -> Nested {
+        Nested {
+            value: 7,
+            simple: Simple {
+                value1: value1.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_diff_same() {
+        let value = nested("1");
+        assert_eq!(diff(&value, &to_string(&value).unwrap()).unwrap(), None);
+
+        let kinds = AbiType::parse_list("uint8,string").unwrap();
+        let s = to_string_params(&(7u8, "abc")).unwrap();
+        assert_eq!(diff_str_params(&kinds, &s, &s).unwrap(), None);
+    }
+
+    #[test]
+    fn test_diff_struct() {
+        let expected = to_string(&nested("12")).unwrap();
+        let difference = diff(&nested("1"), &expected).unwrap().unwrap();
+        assert_eq!(difference.path, ".simple.value1");
+        assert_eq!(difference.left_offset, Some(0x80));
+        assert_eq!(difference.right_offset, Some(0x80));
+        assert!(difference.left.starts_with("length = 1 ("));
+        assert!(difference.right.starts_with("length = 2 ("));
+        assert!(difference
+            .to_string()
+            .starts_with(".simple.value1 at 0x80 (left) and 0x80 (right): length = 1 ("));
+
+        let mut expected = to_string(&nested("1")).unwrap();
+        expected.replace_range(0x20 * 2..0x40 * 2, &format!("{:064x}", 8));
+        let difference = diff(&nested("1"), &expected).unwrap().unwrap();
+        assert_eq!(difference.path, ".value");
+        assert_eq!(difference.left_offset, Some(0x20));
+        assert!(difference.right.starts_with("field 'value': uint64 = 8 ("));
+    }
+
+    #[test]
+    fn test_diff_params() {
+        let value = (1u8, vec![1u16, 2]);
+        let expected = format!("{:064x}{:064x}{:064x}{:064x}{:064x}", 1, 0x40, 2, 1, 3);
+        let difference = diff_params(&value, &expected).unwrap().unwrap();
+        assert_eq!(difference.path, "[1][1]");
+        assert_eq!(difference.left_offset, Some(0x80));
+        assert!(difference.left.starts_with("element 1: uint16 = 2 ("));
+        assert!(difference.right.starts_with("element 1: uint16 = 3 ("));
+
+        // A longer array has words the shorter one does not have
+        let expected = format!(
+            "{:064x}{:064x}{:064x}{:064x}{:064x}{:064x}",
+            1, 0x40, 3, 1, 2, 3
+        );
+        let difference = diff_str_params(
+            &AbiType::parse_list("uint8,uint16[]").unwrap(),
+            &to_string_params(&value).unwrap(),
+            &expected,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(difference.path, "[1]");
+        assert_eq!(difference.left_offset, Some(0x40));
+        assert!(difference.right.starts_with("length = 3 ("));
+    }
+
+    #[test]
+    fn test_diff_encoding() {
+        let kind = AbiType::parse("(uint8,string)").unwrap();
+        let left = format!("{:064x}{:064x}{:064x}{:064x}", 0x20, 1, 0x40, 0);
+
+        // A tail at another offset is the same value, encoded differently
+        let right = format!("{:064x}{:064x}{:064x}{:064x}{:064x}", 0x20, 1, 0x60, 0, 0);
+        let difference = diff_str(&kind, &left, &right).unwrap().unwrap();
+        assert_eq!(difference.path, "[1]");
+        assert_eq!(
+            difference.left,
+            format!("offset of element 1 -> 0x60 ({:064x})", 0x40)
+        );
+
+        // Words that no value refers to
+        let right = format!("{}{:064x}", left, 5);
+        assert_eq!(
+            diff_str(&kind, &left, &right).unwrap(),
+            Some(Difference {
+                path: String::new(),
+                left_offset: None,
+                right_offset: Some(0x80),
+                left: "missing".to_string(),
+                right: format!("{:064x}", 5),
+            })
+        );
+
+        assert!(diff_str(&kind, &left, "0").is_err());
+    }
+}
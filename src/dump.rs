@@ -0,0 +1,856 @@
+//! Annotated hex dumps of encodings, which print every word of an
+//! encoding with its offset and what it encodes, to find where an
+//! encoding differs from what a contract expects.
+//!
+//! ```ignore
+//! let dump = serde_eth::dump::dump(&value)?;
+//! let dump = serde_eth::dump::dump_str(&AbiType::parse("(uint256,string)")?, &s)?;
+//! ```
+//!
+//! A dump looks like this, with byte offsets:
+//!
+//! ```text
+//! 0x00: 0000000000000000000000000000000000000000000000000000000000000020 offset of value -> 0x20
+//! 0x20: 0000000000000000000000000000000000000000000000000000000000000001 length = 1
+//! 0x40: 3100000000000000000000000000000000000000000000000000000000000000 "1" (padded)
+//! ```
+//!
+//! The offsets and lengths are read from the encoding, so a dump can be
+//! made of an encoding that does not decode. Words that nothing points
+//! to are marked as unreferenced.
+
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{cmp, convert::TryFrom};
+
+use serde::ser::{self, Serialize};
+
+use super::{
+    abi_type::{AbiType, AbiValue},
+    error::{Error, ErrorKind, Result},
+    eth, hex_bytes,
+    ser::{to_string, to_string_params},
+    tokens, Address, FixedBytes,
+};
+
+/// Shape is the layout of a value in the encoding: an eth abi type whose
+/// fields may have names
+#[derive(Debug, Clone)]
+pub(crate) enum Shape {
+    /// A value of a type that is encoded in a single word
+    Word(AbiType),
+
+    /// A `string` or `bytes`
+    Bytes(AbiType),
+
+    /// A dynamically sized array of elements of the same shape
+    Array(Box<Shape>),
+
+    /// A dynamically sized array of elements whose shape is known
+    Seq(Vec<Shape>),
+
+    Tuple(Vec<(Field, Shape)>),
+}
+
+/// Field names a value inside of its parent
+#[derive(Debug, Clone)]
+pub(crate) enum Field {
+    /// The value at the root of an encoding
+    Value,
+
+    /// A field of a struct
+    Named(&'static str),
+
+    /// An element of a sequence or a tuple
+    Element(usize),
+}
+
+impl Field {
+    /// label returns how a dump names the field
+    fn label(&self) -> String {
+        match self {
+            Field::Value => "value".to_string(),
+            Field::Named(name) => format!("field '{}'", name),
+            Field::Element(index) => format!("element {}", index),
+        }
+    }
+
+    /// push_path appends the field to the path of its parent, like the
+    /// paths of errors: `.name` for a field and `[i]` for an element
+    fn push_path(&self, path: &mut String) {
+        match self {
+            Field::Value => {}
+            Field::Named(name) => {
+                path.push('.');
+                path.push_str(name);
+            }
+            Field::Element(index) => path.push_str(&format!("[{}]", index)),
+        }
+    }
+}
+
+/// Entry is an annotation of the word at `pos`, made while walking the
+/// value at `path`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Entry {
+    pub(crate) pos: usize,
+    pub(crate) path: String,
+    pub(crate) note: String,
+}
+
+impl Shape {
+    pub(crate) fn from_type(kind: &AbiType) -> Shape {
+        match kind {
+            AbiType::String | AbiType::Bytes => Shape::Bytes(kind.clone()),
+            AbiType::Array(kind) => Shape::Array(Box::new(Shape::from_type(kind))),
+            AbiType::FixedArray(kind, len) => Shape::Tuple(
+                (0..*len)
+                    .map(|i| (Field::Element(i), Shape::from_type(kind)))
+                    .collect(),
+            ),
+            AbiType::Tuple(kinds) => Shape::Tuple(
+                kinds
+                    .iter()
+                    .enumerate()
+                    .map(|(i, kind)| (Field::Element(i), Shape::from_type(kind)))
+                    .collect(),
+            ),
+            _ => Shape::Word(kind.clone()),
+        }
+    }
+
+    fn is_dynamic(&self) -> bool {
+        match self {
+            Shape::Word(_) => false,
+            Shape::Bytes(_) | Shape::Array(_) | Shape::Seq(_) => true,
+            Shape::Tuple(fields) => fields.iter().any(|(_, shape)| shape.is_dynamic()),
+        }
+    }
+
+    /// head_len returns the length of a static value
+    fn head_len(&self) -> usize {
+        match self {
+            Shape::Tuple(fields) => fields.iter().map(|(_, shape)| shape.head_len()).sum(),
+            _ => 32,
+        }
+    }
+}
+
+/// Dump collects the annotations of the words of an encoding
+struct Dump<'a> {
+    data: &'a [u8],
+    entries: Vec<Entry>,
+
+    // path is the path of the value being walked
+    path: String,
+
+    // budget is how many more annotations are made. Offsets in invalid
+    // encodings may point to the same words again and again
+    budget: usize,
+}
+
+impl<'a> Dump<'a> {
+    fn note(&mut self, pos: usize, note: String) {
+        self.budget = self.budget.saturating_sub(1);
+        self.entries.push(Entry {
+            pos,
+            path: self.path.clone(),
+            note,
+        });
+    }
+
+    /// word returns the word at `pos`, if the encoding is long enough
+    fn word(&self, pos: usize) -> Option<[u8; 32]> {
+        let bytes = self.data.get(pos..pos.checked_add(32)?)?;
+        let mut word = [0u8; 32];
+        word.copy_from_slice(bytes);
+        Some(word)
+    }
+
+    /// number returns the word at `pos` as an offset or a length
+    fn number(&self, pos: usize) -> Option<usize> {
+        let word = self.word(pos)?;
+        if word[..24].iter().any(|b| *b != 0) {
+            return None;
+        }
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&word[24..]);
+        usize::try_from(u64::from_be_bytes(bytes)).ok()
+    }
+
+    /// tuple annotates the heads of a tuple that starts at `start`, and
+    /// the tails its offsets point to
+    fn tuple(&mut self, fields: &[(Field, Shape)], start: usize) {
+        let mut head = start;
+        for (field, shape) in fields {
+            let parent = self.path.len();
+            field.push_path(&mut self.path);
+            self.field(&field.label(), shape, start, head);
+            self.path.truncate(parent);
+            head += if shape.is_dynamic() {
+                32
+            } else {
+                shape.head_len()
+            };
+        }
+    }
+
+    /// field annotates a field of a tuple that starts at `start`, whose
+    /// head is at `head`
+    fn field(&mut self, label: &str, shape: &Shape, start: usize, head: usize) {
+        if !shape.is_dynamic() {
+            self.value(label, shape, head);
+            return;
+        }
+
+        match self
+            .number(head)
+            .and_then(|offset| offset.checked_add(start))
+        {
+            Some(pos) if pos < self.data.len() => {
+                self.note(head, format!("offset of {} -> {:#04x}", label, pos));
+                self.value(label, shape, pos);
+            }
+            _ => self.note(head, format!("offset of {} (out of range)", label)),
+        }
+    }
+
+    /// value annotates the encoding of a value at `pos`
+    fn value(&mut self, label: &str, shape: &Shape, pos: usize) {
+        if self.budget == 0 {
+            return;
+        }
+
+        match shape {
+            Shape::Word(kind) => {
+                let note = match self.word(pos) {
+                    Some(word) => describe(kind, word),
+                    None => "missing".to_string(),
+                };
+                self.note(pos, format!("{}: {} = {}", label, kind, note));
+            }
+            Shape::Bytes(kind) => {
+                if let Some(len) = self.length(pos) {
+                    self.bytes(kind, pos + 32, len);
+                }
+            }
+            Shape::Array(element_shape) => {
+                if let Some(len) = self.length(pos) {
+                    let fields: Vec<_> = (0..len)
+                        .map(|i| (Field::Element(i), (**element_shape).clone()))
+                        .collect();
+                    self.tuple(&fields, pos + 32);
+                }
+            }
+            Shape::Seq(shapes) => {
+                if let Some(len) = self.length(pos) {
+                    let fields: Vec<_> = shapes
+                        .iter()
+                        .take(len)
+                        .enumerate()
+                        .map(|(i, shape)| (Field::Element(i), shape.clone()))
+                        .collect();
+                    self.tuple(&fields, pos + 32);
+                }
+            }
+            Shape::Tuple(fields) => self.tuple(fields, pos),
+        }
+    }
+
+    /// length annotates the length at `pos` of a dynamic value, and
+    /// returns it unless the content would not fit in the encoding
+    fn length(&mut self, pos: usize) -> Option<usize> {
+        match self.number(pos) {
+            Some(len) if len <= self.data.len().saturating_sub(pos + 32) => {
+                self.note(pos, format!("length = {}", len));
+                Some(len)
+            }
+            Some(len) => {
+                self.note(pos, format!("length = {} (exceeds the input)", len));
+                None
+            }
+            None if self.word(pos).is_some() => {
+                self.note(pos, "length (out of range)".to_string());
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// bytes annotates the words of the content of a `string` or `bytes`
+    fn bytes(&mut self, kind: &AbiType, start: usize, len: usize) {
+        for pos in (start..start + len).step_by(32) {
+            let end = cmp::min(pos + 32, start + len);
+            let chunk = &self.data[pos..end];
+            let mut note = match kind {
+                AbiType::String => format!("{:?}", String::from_utf8_lossy(chunk)),
+                _ => hex_bytes::encode(chunk),
+            };
+            if end - pos < 32 {
+                note.push_str(" (padded)");
+            }
+            self.note(pos, note);
+        }
+    }
+}
+
+/// walk annotates the words of an encoding of a value of the given shape,
+/// in the order the value is walked. In params mode a tuple at the root is
+/// the list of parameters of a function call
+pub(crate) fn walk(shape: &Shape, data: &[u8], params: bool) -> Vec<Entry> {
+    let mut dump = Dump {
+        data,
+        entries: Vec::new(),
+        path: String::new(),
+        budget: 16 * (data.len() / 32 + 1),
+    };
+
+    match shape {
+        Shape::Tuple(fields) if params => dump.tuple(fields, 0),
+        shape => dump.tuple(&[(Field::Value, shape.clone())], 0),
+    }
+    dump.entries
+}
+
+/// chunk returns the word at `pos` as hex, or "missing" past the end of
+/// the encoding. The last word may be short
+pub(crate) fn chunk(data: &[u8], pos: usize) -> String {
+    match data.get(pos..cmp::min(pos.saturating_add(32), data.len())) {
+        Some(bytes) if !bytes.is_empty() => hex::encode(bytes),
+        _ => "missing".to_string(),
+    }
+}
+
+/// render prints every word of an encoding with its annotations
+fn render(data: &[u8], entries: Vec<Entry>) -> String {
+    let mut notes: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for entry in entries {
+        notes.entry(entry.pos).or_default().push(entry.note);
+    }
+
+    let mut out = String::new();
+    let len = eth::padded_len(data.len());
+    let mut positions: Vec<usize> = (0..len).step_by(32).collect();
+    positions.extend(notes.keys().filter(|pos| *pos % 32 != 0 || **pos >= len));
+    positions.sort_unstable();
+    positions.dedup();
+
+    for pos in positions {
+        let note = match notes.get(&pos) {
+            Some(notes) => notes.join("; "),
+            None => "unreferenced".to_string(),
+        };
+        out.push_str(&format!(
+            "{:#04x}: {:<64} {}\n",
+            pos,
+            chunk(data, pos),
+            note
+        ));
+    }
+    out
+}
+
+/// describe returns what a word means as a value of a type encoded
+/// in a single word
+fn describe(kind: &AbiType, word: [u8; 32]) -> String {
+    let valid: Result<[u8; 32]> = match kind {
+        AbiType::Uint(bits) => tokens::uint_word(FixedBytes(word), *bits),
+        AbiType::Int(bits) => tokens::int_word(FixedBytes(word), *bits),
+        AbiType::FixedBytes(len) => tokens::fixed_bytes_word(FixedBytes(word), *len),
+        AbiType::Address if word[..12].iter().any(|b| *b != 0) => Err(invalid()),
+        AbiType::Bool if word[..31].iter().any(|b| *b != 0) || word[31] > 1 => Err(invalid()),
+        _ => Ok(word),
+    };
+    if valid.is_err() {
+        return format!("invalid {}", kind);
+    }
+
+    match kind {
+        AbiType::Uint(_) => AbiValue::Uint(word).to_string(),
+        AbiType::Int(_) => AbiValue::Int(word).to_string(),
+        AbiType::FixedBytes(len) => hex_bytes::encode(&word[..*len]),
+        AbiType::Address => Address::from_slice(&word[12..])
+            .map(|address| address.to_string())
+            .unwrap_or_default(),
+        AbiType::Bool => (word[31] == 1).to_string(),
+        _ => hex_bytes::encode(word),
+    }
+}
+
+fn invalid() -> Error {
+    Error::new(ErrorKind::Custom, "invalid word")
+}
+
+/// dump_shape dumps an encoding of a value of the given shape. In params
+/// mode a tuple at the root is the list of parameters of a function call
+fn dump_shape(shape: Shape, s: &str, params: bool) -> Result<String> {
+    let data = hex::decode(s).map_err(Error::hex_parsing)?;
+    let entries = walk(&shape, &data, params);
+    Ok(render(&data, entries))
+}
+
+/// dump encodes a value like `to_string` does and dumps the encoding,
+/// with the names of the fields of structs
+pub fn dump<T: ?Sized + Serialize>(value: &T) -> Result<String> {
+    dump_shape(shape_of(value)?, &to_string(value)?, false)
+}
+
+/// dump_params encodes a value like `to_string_params` does and dumps
+/// the encoding
+pub fn dump_params<T: ?Sized + Serialize>(value: &T) -> Result<String> {
+    dump_shape(shape_of(value)?, &to_string_params(value)?, true)
+}
+
+/// dump_str dumps the encoding of a value of the given type, as encoded
+/// by `to_string`
+pub fn dump_str(kind: &AbiType, s: &str) -> Result<String> {
+    dump_shape(Shape::from_type(kind), s, false)
+}
+
+/// dump_str_params dumps the encoding of the list of parameters of a
+/// function call
+pub fn dump_str_params(kinds: &[AbiType], s: &str) -> Result<String> {
+    dump_shape(Shape::from_type(&AbiType::Tuple(kinds.to_vec())), s, true)
+}
+
+/// shape_of returns the shape of a value, with the names of the fields of
+/// structs
+pub(crate) fn shape_of<T: ?Sized + Serialize>(value: &T) -> Result<Shape> {
+    value.serialize(ShapeSerializer)
+}
+
+/// ShapeSerializer computes the shape of a value as the serializer
+/// encodes it
+struct ShapeSerializer;
+
+/// ShapeCompound collects the shapes of the elements of a compound
+struct ShapeCompound {
+    fields: Vec<(Field, Shape)>,
+    tuple: bool,
+}
+
+impl ShapeCompound {
+    fn push<T: ?Sized + Serialize>(&mut self, field: Field, value: &T) -> Result<()> {
+        self.fields.push((field, value.serialize(ShapeSerializer)?));
+        Ok(())
+    }
+
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(Field::Element(self.fields.len()), value)
+    }
+
+    fn finish(self) -> Result<Shape> {
+        if self.tuple {
+            Ok(Shape::Tuple(self.fields))
+        } else {
+            Ok(Shape::Seq(
+                self.fields.into_iter().map(|(_, shape)| shape).collect(),
+            ))
+        }
+    }
+}
+
+impl ser::SerializeSeq for ShapeCompound {
+    type Ok = Shape;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Shape> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for ShapeCompound {
+    type Ok = Shape;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Shape> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for ShapeCompound {
+    type Ok = Shape;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Shape> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for ShapeCompound {
+    type Ok = Shape;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Shape> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for ShapeCompound {
+    type Ok = Shape;
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, _key: &T) -> Result<()> {
+        Err(Error::not_implemented("map"))
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, _value: &T) -> Result<()> {
+        Err(Error::not_implemented("map"))
+    }
+
+    fn end(self) -> Result<Shape> {
+        Err(Error::not_implemented("map"))
+    }
+}
+
+impl ser::SerializeStruct for ShapeCompound {
+    type Ok = Shape;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.push(Field::Named(key), value)
+    }
+
+    fn end(self) -> Result<Shape> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for ShapeCompound {
+    type Ok = Shape;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.push(Field::Named(key), value)
+    }
+
+    fn end(self) -> Result<Shape> {
+        self.finish()
+    }
+}
+
+/// fixed_shape returns the shape of the newtypes the serializer encodes
+/// as a single word
+fn fixed_shape(name: &str) -> Option<Shape> {
+    eth::Fixed::get(name).map(|fixed| {
+        Shape::Word(match fixed {
+            eth::Fixed::H256 => AbiType::FixedBytes(32),
+            eth::Fixed::H160 => AbiType::Address,
+            eth::Fixed::U256 => AbiType::Uint(256),
+        })
+    })
+}
+
+impl ser::Serializer for ShapeSerializer {
+    type Ok = Shape;
+    type Error = Error;
+
+    type SerializeSeq = ShapeCompound;
+    type SerializeTuple = ShapeCompound;
+    type SerializeTupleStruct = ShapeCompound;
+    type SerializeTupleVariant = ShapeCompound;
+    type SerializeMap = ShapeCompound;
+    type SerializeStruct = ShapeCompound;
+    type SerializeStructVariant = ShapeCompound;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, _value: bool) -> Result<Shape> {
+        Ok(Shape::Word(AbiType::Bool))
+    }
+
+    fn serialize_i8(self, _value: i8) -> Result<Shape> {
+        Ok(Shape::Word(AbiType::Int(8)))
+    }
+
+    fn serialize_i16(self, _value: i16) -> Result<Shape> {
+        Ok(Shape::Word(AbiType::Int(16)))
+    }
+
+    fn serialize_i32(self, _value: i32) -> Result<Shape> {
+        Ok(Shape::Word(AbiType::Int(32)))
+    }
+
+    fn serialize_i64(self, _value: i64) -> Result<Shape> {
+        Ok(Shape::Word(AbiType::Int(64)))
+    }
+
+    fn serialize_u8(self, _value: u8) -> Result<Shape> {
+        Ok(Shape::Word(AbiType::Uint(8)))
+    }
+
+    fn serialize_u16(self, _value: u16) -> Result<Shape> {
+        Ok(Shape::Word(AbiType::Uint(16)))
+    }
+
+    fn serialize_u32(self, _value: u32) -> Result<Shape> {
+        Ok(Shape::Word(AbiType::Uint(32)))
+    }
+
+    fn serialize_u64(self, _value: u64) -> Result<Shape> {
+        Ok(Shape::Word(AbiType::Uint(64)))
+    }
+
+    fn serialize_f32(self, _value: f32) -> Result<Shape> {
+        Err(Error::not_implemented("f32"))
+    }
+
+    fn serialize_f64(self, _value: f64) -> Result<Shape> {
+        Err(Error::not_implemented("f64"))
+    }
+
+    fn serialize_char(self, _value: char) -> Result<Shape> {
+        Ok(Shape::Bytes(AbiType::String))
+    }
+
+    fn serialize_str(self, _value: &str) -> Result<Shape> {
+        Ok(Shape::Bytes(AbiType::String))
+    }
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<Shape> {
+        Ok(Shape::Bytes(AbiType::Bytes))
+    }
+
+    fn serialize_none(self) -> Result<Shape> {
+        Ok(Shape::Seq(Vec::new()))
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Shape> {
+        Ok(Shape::Seq(vec![value.serialize(ShapeSerializer)?]))
+    }
+
+    fn serialize_unit(self) -> Result<Shape> {
+        Ok(Shape::Tuple(Vec::new()))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Shape> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<Shape> {
+        self.serialize_unit()
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Shape> {
+        match fixed_shape(name) {
+            Some(shape) => Ok(shape),
+            None => value.serialize(self),
+        }
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<Shape> {
+        self.serialize_newtype_struct(name, value)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<ShapeCompound> {
+        Ok(ShapeCompound {
+            fields: Vec::new(),
+            tuple: false,
+        })
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<ShapeCompound> {
+        Ok(ShapeCompound {
+            fields: Vec::new(),
+            tuple: true,
+        })
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<ShapeCompound> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<ShapeCompound> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<ShapeCompound> {
+        Err(Error::not_implemented("map"))
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<ShapeCompound> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<ShapeCompound> {
+        self.serialize_tuple(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{dump, dump_params, dump_str, dump_str_params};
+    use crate::{
+        abi_type::{AbiType, AbiValue},
+        Address,
+    };
+    use alloc::{
+        string::{String, ToString},
+        vec::Vec,
+    };
+    use serde::Serialize;
+
+    /// lines returns the offsets and notes of a dump, without the words
+    fn lines(dump: &str) -> Vec<String> {
+        dump.lines()
+            .map(|line| {
+                let (pos, rest) = line.split_once(": ").unwrap();
+                format!("{}: {}", pos, &rest[65..])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dump_struct() {
+        #[derive(Serialize)]
+        struct Simple {
+            value1: String,
+        }
+
+        #[derive(Serialize)]
+        struct Nested {
+            value: u64,
+            simple: Simple,
+        }
+
+        let value = Nested {
+            value: 7,
+            simple: Simple {
+                value1: "1".to_string(),
+            },
+        };
+        assert_eq!(
+            lines(&dump(&value).unwrap()),
+            vec![
+                "0x00: offset of value -> 0x20",
+                "0x20: field 'value': uint64 = 7",
+                "0x40: offset of field 'simple' -> 0x60",
+                "0x60: offset of field 'value1' -> 0x80",
+                "0x80: length = 1",
+                "0xa0: \"1\" (padded)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dump_words() {
+        let dump = dump(&vec![1u8, 2]).unwrap();
+        assert_eq!(
+            dump.lines().nth(2).unwrap(),
+            format!("0x40: {:064x} element 0: uint8 = 1", 1)
+        );
+
+        let value = (Address::from([0x11; 20]), -2i8, true);
+        assert_eq!(
+            lines(&dump_params(&value).unwrap()),
+            vec![
+                "0x00: element 0: address = 0x1111111111111111111111111111111111111111",
+                "0x20: element 1: int8 = -2",
+                "0x40: element 2: bool = true",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dump_str() {
+        let kind = AbiType::parse("(uint8,bytes)[]").unwrap();
+        let value = AbiValue::Array(vec![AbiValue::Tuple(vec![
+            AbiType::Uint(8).parse_value("1").unwrap(),
+            AbiValue::Bytes(vec![0xab; 33]),
+        ])]);
+        let s = crate::to_string(&value).unwrap();
+        assert_eq!(
+            lines(&dump_str(&kind, &s).unwrap()),
+            vec![
+                "0x00: offset of value -> 0x20".to_string(),
+                "0x20: length = 1".to_string(),
+                "0x40: offset of element 0 -> 0x60".to_string(),
+                "0x60: element 0: uint8 = 1".to_string(),
+                "0x80: offset of element 1 -> 0xa0".to_string(),
+                "0xa0: length = 33".to_string(),
+                format!("0xc0: 0x{}", "ab".repeat(32)),
+                "0xe0: 0xab (padded)".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dump_invalid() {
+        let kinds = AbiType::parse_list("uint8,string").unwrap();
+        let s = [
+            format!("{:064x}", 0x100),
+            format!("{:064x}", 0x60),
+            format!("{:064x}", 5),
+            format!("{:064x}", 0x1000),
+        ]
+        .concat();
+        assert_eq!(
+            lines(&dump_str_params(&kinds, &s).unwrap()),
+            vec![
+                "0x00: element 0: uint8 = invalid uint8",
+                "0x20: offset of element 1 -> 0x60",
+                "0x40: unreferenced",
+                "0x60: length = 4096 (exceeds the input)",
+            ]
+        );
+        assert!(dump_str_params(&kinds, "zz").is_err());
+    }
+}
@@ -1,6 +1,15 @@
 use serde::{de, ser};
 
-use std::{error, fmt, io, result};
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt, result};
+#[cfg(feature = "std")]
+use std::error;
+
+use super::io;
 
 pub struct Error {
     err: Box<ErrorImpl>,
@@ -10,13 +19,37 @@ pub type Result<T> = result::Result<T, Error>;
 
 impl Error {
     pub fn classify(&self) -> Category {
+        if let ErrorCode::TupleHint(_, _) = self.err.code {
+            return Category::TupleHint;
+        }
+
+        match self.kind() {
+            ErrorKind::UnexpectedEof => Category::EOF,
+            ErrorKind::Io => Category::IO,
+            ErrorKind::LimitExceeded => Category::Limit,
+            ErrorKind::Unsupported { .. } => Category::Internal,
+            ErrorKind::Custom => Category::Data,
+            ErrorKind::TrailingData
+            | ErrorKind::IntegerOverflow
+            | ErrorKind::InvalidBool
+            | ErrorKind::InvalidUtf8
+            | ErrorKind::InvalidOffset
+            | ErrorKind::InvalidHex
+            | ErrorKind::InvalidChecksum
+            | ErrorKind::InvalidLength
+            | ErrorKind::NonCanonical => Category::Syntax,
+        }
+    }
+
+    /// kind returns what went wrong. For tuple hints, it is the kind
+    /// of the error that caused the hint
+    pub fn kind(&self) -> ErrorKind {
         match self.err.code {
-            ErrorCode::TupleHint(_, _) => Category::TupleHint,
-            ErrorCode::Message(_) => Category::Data,
-            ErrorCode::IO(_) => Category::IO,
-            ErrorCode::NotImplemented => Category::Internal,
-            ErrorCode::HexParsing(_) => Category::Syntax,
-            ErrorCode::Parsing(_) => Category::Syntax,
+            ErrorCode::TupleHint(_, ref err) => err.kind(),
+            ErrorCode::Message(kind, _) => kind,
+            #[cfg(feature = "std")]
+            ErrorCode::IO(_) => ErrorKind::Io,
+            ErrorCode::HexParsing(_) => ErrorKind::InvalidHex,
         }
     }
 
@@ -46,8 +79,48 @@ impl Error {
     pub fn is_eof(&self) -> bool {
         self.classify() == Category::EOF
     }
+
+    pub fn is_limit(&self) -> bool {
+        self.classify() == Category::Limit
+    }
+
+    /// offset returns the position in the input, in bytes of its hex
+    /// encoding, of the data that was being decoded when the error happened
+    pub fn offset(&self) -> Option<u64> {
+        self.err.position.as_ref().map(|position| position.offset)
+    }
+
+    /// scopes returns the offsets at which each of the sequences and
+    /// tuples that were being decoded begin, starting with the outermost
+    pub fn scopes(&self) -> &[usize] {
+        self.err
+            .position
+            .as_ref()
+            .map(|position| &position.scopes[..])
+            .unwrap_or(&[])
+    }
+
+    /// path returns the path to the field or element that was being
+    /// decoded, e.g. `.field[3].inner`. The path is empty for the top
+    /// level value
+    pub fn path(&self) -> Option<&str> {
+        self.err
+            .position
+            .as_ref()
+            .map(|position| &position.path[..])
+    }
+
+    pub(crate) fn with_position(mut self, offset: u64, scopes: Vec<usize>, path: String) -> Self {
+        self.err.position = Some(Position {
+            offset,
+            scopes,
+            path,
+        });
+        self
+    }
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum Category {
     TupleHint,
@@ -56,6 +129,60 @@ pub enum Category {
     Data,
     EOF,
     Internal,
+
+    /// The input exceeds one of the limits set through the
+    /// deserializer options
+    Limit,
+}
+
+/// ErrorKind describes what went wrong when encoding or decoding
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ErrorKind {
+    /// The input ended before the value was completely decoded
+    UnexpectedEof,
+
+    /// There is input left after the value was decoded
+    TrailingData,
+
+    /// A decoded integer does not fit in the integer type
+    IntegerOverflow,
+
+    /// A boolean is neither 0 nor 1
+    InvalidBool,
+
+    /// A string or char is not valid utf8
+    InvalidUtf8,
+
+    /// An offset does not point to where the content is expected
+    InvalidOffset,
+
+    /// The input is not valid hex
+    InvalidHex,
+
+    /// The letter casing of an address does not match its EIP-55
+    /// checksum
+    InvalidChecksum,
+
+    /// The length of a value does not match what the type expects,
+    /// e.g. an option with more than one element
+    InvalidLength,
+
+    /// The input is not canonically encoded, which is only
+    /// checked in strict mode
+    NonCanonical,
+
+    /// The input exceeds one of the limits set through the
+    /// deserializer options
+    LimitExceeded,
+
+    /// The type cannot be encoded or decoded with the eth abi
+    Unsupported { what: &'static str },
+
+    /// Reading or writing failed
+    Io,
+
+    /// Error raised by a `Serialize` or `Deserialize` implementation
+    Custom,
 }
 
 #[derive(Clone, Copy)]
@@ -72,93 +199,100 @@ impl TupleHint {
 
 struct ErrorImpl {
     code: ErrorCode,
+    position: Option<Position>,
+}
+
+/// Position is where in the input the deserializer was when
+/// an error happened
+struct Position {
+    offset: u64,
+    scopes: Vec<usize>,
+    path: String,
 }
 
 pub enum ErrorCode {
     TupleHint(TupleHint, Error),
-    Message(Box<str>),
+    Message(ErrorKind, Box<str>),
+    #[cfg(feature = "std")]
     IO(io::Error),
-    NotImplemented,
     HexParsing(hex::FromHexError),
-    Parsing(Box<str>),
 }
 
 impl Error {
-    pub(crate) fn message(s: &str) -> Self {
+    pub(crate) fn new(kind: ErrorKind, s: &str) -> Self {
         Error {
             err: Box::new(ErrorImpl {
-                code: ErrorCode::Message(s.to_string().into_boxed_str()),
+                position: None,
+                code: ErrorCode::Message(kind, s.to_string().into_boxed_str()),
             }),
         }
     }
 
+    #[cfg(feature = "std")]
     pub(crate) fn io(error: io::Error) -> Self {
         Error {
             err: Box::new(ErrorImpl {
+                position: None,
                 code: ErrorCode::IO(error),
             }),
         }
     }
 
+    /// io keeps only the message of the error without std, where the
+    /// readers and writers are in memory and rarely fail
+    #[cfg(not(feature = "std"))]
+    pub(crate) fn io(error: io::Error) -> Self {
+        Error::new(ErrorKind::Io, &error.to_string())
+    }
+
     pub(crate) fn hint(hint: TupleHint, cause: Error) -> Self {
         Error {
             err: Box::new(ErrorImpl {
+                position: None,
                 code: ErrorCode::TupleHint(hint, cause),
             }),
         }
     }
 
-    pub(crate) fn not_implemented() -> Self {
-        Error {
-            err: Box::new(ErrorImpl {
-                code: ErrorCode::NotImplemented,
-            }),
-        }
+    pub(crate) fn not_implemented(what: &'static str) -> Self {
+        Error::new(ErrorKind::Unsupported { what }, "not implemented")
     }
 
     pub(crate) fn hex_parsing(error: hex::FromHexError) -> Self {
         Error {
             err: Box::new(ErrorImpl {
+                position: None,
                 code: ErrorCode::HexParsing(error),
             }),
         }
     }
-
-    pub(crate) fn parsing(s: &str) -> Self {
-        Error {
-            err: Box::new(ErrorImpl {
-                code: ErrorCode::Parsing(s.to_string().into_boxed_str()),
-            }),
-        }
-    }
 }
 
 impl fmt::Display for ErrorCode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             ErrorCode::TupleHint(_, ref err) => fmt::Display::fmt(err, f),
-            ErrorCode::Message(ref msg) => f.write_str(msg),
+            ErrorCode::Message(_, ref msg) => f.write_str(msg),
+            #[cfg(feature = "std")]
             ErrorCode::IO(ref err) => fmt::Display::fmt(err, f),
-            ErrorCode::NotImplemented => f.write_str("not implemented"),
             ErrorCode::HexParsing(ref err) => fmt::Display::fmt(err, f),
-            ErrorCode::Parsing(ref msg) => f.write_str(msg),
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl error::Error for Error {
+    #[allow(deprecated)]
     fn description(&self) -> &str {
         match self.err.code {
             ErrorCode::TupleHint(_, ref err) => error::Error::description(err),
             ErrorCode::IO(ref err) => error::Error::description(err),
-            ErrorCode::Message(ref str) => str,
-            ErrorCode::NotImplemented => "not implemented",
-            ErrorCode::HexParsing(ref err) => error::Error::description(err),
-            ErrorCode::Parsing(ref str) => str,
+            ErrorCode::Message(_, ref str) => str,
+            ErrorCode::HexParsing(ref err) => hex_description(err),
         }
     }
 
-    fn cause(&self) -> Option<&error::Error> {
+    fn cause(&self) -> Option<&dyn error::Error> {
         match self.err.code {
             ErrorCode::TupleHint(_, ref err) => Some(err),
             ErrorCode::IO(ref err) => Some(err),
@@ -168,6 +302,20 @@ impl error::Error for Error {
     }
 }
 
+/// serde requires its own error trait in place of std's without std
+#[cfg(not(feature = "std"))]
+impl de::StdError for Error {}
+
+/// hex_description returns the description of a hex parsing error
+#[cfg(feature = "std")]
+fn hex_description(err: &hex::FromHexError) -> &'static str {
+    match err {
+        hex::FromHexError::InvalidHexCharacter { .. } => "invalid character",
+        hex::FromHexError::OddLength => "odd number of digits",
+        hex::FromHexError::InvalidStringLength => "invalid string length",
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Display::fmt(&*self.err, f)
@@ -176,13 +324,31 @@ impl fmt::Display for Error {
 
 impl fmt::Display for ErrorImpl {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        fmt::Display::fmt(&self.code, f)
+        fmt::Display::fmt(&self.code, f)?;
+
+        if let Some(ref position) = self.position {
+            if !position.path.is_empty() {
+                write!(f, " at {}", position.path)?;
+            }
+
+            write!(f, " (offset {}", position.offset)?;
+            for (i, scope) in position.scopes.iter().enumerate() {
+                if i == 0 {
+                    write!(f, ", scopes at {}", scope)?;
+                } else {
+                    write!(f, ", {}", scope)?;
+                }
+            }
+            f.write_str(")")?;
+        }
+
+        Ok(())
     }
 }
 
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Error: {:?}", self.err.code.to_string(),)
+        write!(f, "Error: {:?}", self.err.to_string(),)
     }
 }
 
@@ -191,13 +357,20 @@ impl de::Error for Error {
         make_error(msg.to_string())
     }
 
-    fn invalid_type(unexp: de::Unexpected, exp: &de::Expected) -> Self {
+    fn invalid_type(unexp: de::Unexpected, exp: &dyn de::Expected) -> Self {
         if let de::Unexpected::Unit = unexp {
             Error::custom(format_args!("invalid type: null, expected {}", exp))
         } else {
             Error::custom(format_args!("invalid type: {}, expected {}", unexp, exp))
         }
     }
+
+    fn invalid_length(len: usize, exp: &dyn de::Expected) -> Self {
+        Error::new(
+            ErrorKind::InvalidLength,
+            &format!("invalid length {}, expected {}", len, exp),
+        )
+    }
 }
 
 impl ser::Error for Error {
@@ -212,7 +385,8 @@ impl ser::Error for Error {
 fn make_error(msg: String) -> Error {
     Error {
         err: Box::new(ErrorImpl {
-            code: ErrorCode::Message(msg.into_boxed_str()),
+            position: None,
+            code: ErrorCode::Message(ErrorKind::Custom, msg.into_boxed_str()),
         }),
     }
 }
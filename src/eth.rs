@@ -1,79 +1,110 @@
-use super::error::Error;
-use oasis_std::types::U256;
+use alloc::{string::ToString, vec::Vec};
 
-fn parse_int(bytes: &[u8], size: usize) -> Result<i64, Error> {
-    if bytes.len() != 64 {
-        return Err(Error::parsing("invalid byte array size for uint"));
-    }
+use super::error::{Error, ErrorKind};
 
-    let decoded = hex::decode(bytes).map_err(Error::hex_parsing)?;
-    let value: U256 = (&decoded[..]).into();
+/// Word is a 256-bit unsigned integer as four limbs, the most significant
+/// first. Decoding integers needs no more arithmetic than what it has
+#[derive(Clone, Copy)]
+struct Word([u64; 4]);
 
-    // if value is supposed to be a positive integer
-    if value.leading_zeros() > 0 {
-        if value.bits() > size {
-            return Err(Error::parsing(
-                "decoded integer does not fit in integer of specified size",
-            ));
+impl Word {
+    fn from_be_bytes(bytes: &[u8]) -> Word {
+        let mut limbs = [0u64; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = chunk.iter().fold(0, |n, b| n << 8 | u64::from(*b));
         }
-        return Ok(value.low_u64() as i64);
+        Word(limbs)
     }
 
-    let (n, overflows) = value.overflowing_neg();
-    if !overflows {
-        // if it is a negative integer negating it must overflow
-        return Err(Error::parsing(
-            "decoded integer does not fit in integer of specified size",
-        ));
+    fn leading_zeros(&self) -> usize {
+        let mut zeros = 0;
+        for limb in &self.0 {
+            if *limb != 0 {
+                return zeros + limb.leading_zeros() as usize;
+            }
+            zeros += 64;
+        }
+        zeros
     }
 
-    let (n, overflows) = n.overflowing_add(U256::one());
-    if overflows || n.bits() > size {
-        return Err(Error::parsing(
-            "decoded integer does not fit in integer of specified size",
-        ));
+    fn bits(&self) -> usize {
+        256 - self.leading_zeros()
+    }
+
+    fn low_u64(&self) -> u64 {
+        self.0[3]
     }
 
-    let int = n.low_u64() as i64;
-    Ok(if int < 0 { int } else { -int })
+    /// not returns the ones' complement of the word
+    fn not(&self) -> Word {
+        Word(self.0.map(|limb| !limb))
+    }
 }
 
-fn parse_uint(bytes: &[u8], size: usize) -> Result<u64, Error> {
+fn parse_word(bytes: &[u8]) -> Result<Word, Error> {
     if bytes.len() != 64 {
-        return Err(Error::parsing("invalid byte array size for uint"));
+        return Err(Error::new(
+            ErrorKind::InvalidLength,
+            "invalid byte array size for uint",
+        ));
     }
 
     let decoded = hex::decode(bytes).map_err(Error::hex_parsing)?;
-    let value: U256 = (&decoded[..]).into();
+    Ok(Word::from_be_bytes(&decoded))
+}
+
+fn overflow_error() -> Error {
+    Error::new(
+        ErrorKind::IntegerOverflow,
+        "decoded integer does not fit in integer of specified size",
+    )
+}
+
+fn parse_int(bytes: &[u8], size: usize) -> Result<i64, Error> {
+    let value = parse_word(bytes)?;
+
+    // a value fits in size bits when all the bits above the sign bit are
+    // copies of it: zeros for positive values, and ones for negative
+    // values, which are the zeros of their ones' complement
+    let magnitude = if value.leading_zeros() > 0 {
+        value
+    } else {
+        value.not()
+    };
+    if magnitude.bits() >= size {
+        return Err(overflow_error());
+    }
+
+    // the low 64 bits hold the value sign extended
+    Ok(value.low_u64() as i64)
+}
+
+fn parse_uint(bytes: &[u8], size: usize) -> Result<u64, Error> {
+    let value = parse_word(bytes)?;
 
     // if value is supposed to be a positive integer
     if value.leading_zeros() > 0 {
         if value.bits() > size {
-            return Err(Error::parsing(
-                "decoded integer does not fit in integer of specified size",
-            ));
+            return Err(overflow_error());
         }
         Ok(value.low_u64())
     } else {
-        Err(Error::parsing(
-            "decoded integer does not fit in integer of specified size",
-        ))
+        Err(overflow_error())
     }
 }
 
-fn gen_uint(value: u64) -> String {
-    let uint: U256 = value.into();
-    let mut bytes = [0u8; 32];
-    uint.to_big_endian(&mut bytes[..]);
-    hex::encode(&bytes[..])
-}
-
 pub(crate) fn decode_bool(bytes: &[u8]) -> Result<bool, Error> {
-    let value = parse_uint(bytes, 1)?;
+    let value = parse_uint(bytes, 1).map_err(|err| match err.kind() {
+        ErrorKind::IntegerOverflow => Error::new(ErrorKind::InvalidBool, &err.to_string()),
+        _ => err,
+    })?;
     match value {
         0 => Ok(false),
         1 => Ok(true),
-        _ => Err(Error::parsing("invalid value for boolean")),
+        _ => Err(Error::new(
+            ErrorKind::InvalidBool,
+            "invalid value for boolean",
+        )),
     }
 }
 
@@ -88,7 +119,8 @@ pub(crate) fn decode_int(bytes: &[u8], size: usize) -> Result<i64, Error> {
 pub(crate) fn decode_bytes(bytes: &[u8], len: usize) -> Result<Vec<u8>, Error> {
     let decoded = hex::decode(bytes).map_err(Error::hex_parsing)?;
     if len > decoded.len() {
-        Err(Error::parsing(
+        Err(Error::new(
+            ErrorKind::InvalidLength,
             "decoded bytes are smaller than the required length",
         ))
     } else {
@@ -96,96 +128,43 @@ pub(crate) fn decode_bytes(bytes: &[u8], len: usize) -> Result<Vec<u8>, Error> {
     }
 }
 
-pub(crate) fn encode_bool(value: bool) -> String {
-    let uint = if value { 1 } else { 0 };
-    gen_uint(uint)
-}
-
-pub(crate) fn encode_i64(value: i64) -> String {
-    if value >= 0 {
-        return gen_uint(value as u64);
-    }
+const HEX: &[u8; 16] = b"0123456789abcdef";
 
-    let value = (!value as u64) + 1;
-    let uint: U256 = value.into();
-    let (uint, overflows) = uint.overflowing_sub(U256::one());
-    if overflows {
-        if uint != U256::zero() {
-            panic!("expected overflow from negating uint");
-        }
+/// write_hex writes the hex encoding of `bytes` at the start of `out`
+pub(crate) fn write_hex(out: &mut [u8], bytes: &[u8]) {
+    for (chunk, byte) in out.chunks_exact_mut(2).zip(bytes) {
+        chunk[0] = HEX[(byte >> 4) as usize];
+        chunk[1] = HEX[(byte & 0xf) as usize];
     }
-
-    // if original value was -1, and it is set as 0 for U256,
-    // overflowing_neg() leaves 0 unchanged. So we do this,
-    // in order to get back the 0xfff..ff (32 f's)
-    // value which is the correct encoding
-    let uint = if uint == U256::zero() {
-        let (uint, _) = U256::one().overflowing_neg();
-        let (uint, _) = uint.overflowing_add(U256::one());
-        uint
-    } else {
-        let (uint, overflows) = uint.overflowing_neg();
-        if !overflows {
-            panic!("expected overflow from negating uint");
-        }
-        uint
-    };
-
-    let mut bytes = [0u8; 32];
-    uint.to_big_endian(&mut bytes[..]);
-    hex::encode(&bytes[..])
 }
 
-pub(crate) fn encode_u64(value: u64) -> String {
-    gen_uint(value)
+/// write_u64 writes the encoding of an unsigned integer to a word
+pub(crate) fn write_u64(word: &mut [u8], value: u64) {
+    word[..48].iter_mut().for_each(|b| *b = b'0');
+    write_hex(&mut word[48..], &value.to_be_bytes());
 }
 
-pub(crate) fn encode_bytes(value: &[u8]) -> String {
-    let encoding = encode_bytes_dynamic(value);
-
-    // just set the default offset to 0x20
-    let offset = "0000000000000000000000000000000000000000000000000000000000000020";
-    let mut result = String::new();
-    result.push_str(offset);
-    result.push_str(encoding.size());
-    result.push_str(encoding.content());
-    result
+/// write_i64 writes the encoding of a signed integer to a word, sign
+/// extended to 256 bits
+pub(crate) fn write_i64(word: &mut [u8], value: i64) {
+    let sign = if value < 0 { b'f' } else { b'0' };
+    word[..48].iter_mut().for_each(|b| *b = sign);
+    write_hex(&mut word[48..], &value.to_be_bytes());
 }
 
-pub(crate) struct DynamicSizedEncoding {
-    size: String,
-    content: String,
+/// write_bool writes the encoding of a boolean to a word
+pub(crate) fn write_bool(word: &mut [u8], value: bool) {
+    write_u64(word, value as u64)
 }
 
-impl DynamicSizedEncoding {
-    pub(crate) fn size(&self) -> &str {
-        &self.size
-    }
-
-    pub(crate) fn content(&self) -> &str {
-        &self.content
-    }
+/// padded_len returns the length of `len` bytes padded to a multiple
+/// of the word size
+pub(crate) fn padded_len(len: usize) -> usize {
+    (len + 31) & !31
 }
 
-pub(crate) fn encode_bytes_dynamic(value: &[u8]) -> DynamicSizedEncoding {
-    let len = value.len() as u64;
-    let base = (len >> 5) << 5;
-    let remain = if len - base == 0u64 { 0 } else { 1 };
-    let payload_len = base + (remain << 5);
-    let total_len = payload_len; // offset and length
-    let mut payload = vec![0u8; total_len as usize];
-
-    for i in 0..value.len() {
-        payload[i] = value[i];
-    }
-
-    let mut size = vec![0u8; 32];
-    let len: U256 = value.len().into();
-    len.to_big_endian(&mut size[..]);
-
-    let size = hex::encode(size);
-    let content = hex::encode(payload);
-    DynamicSizedEncoding { size, content }
+pub(crate) fn keccak256(data: &[u8]) -> [u8; 32] {
+    tiny_keccak::keccak256(data)
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -0,0 +1,308 @@
+//! Serialize the types of `primitive-types` and `ethereum-types` as their
+//! eth abi types. To be used with `#[serde(with = ...)]`:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Log {
+//!     #[serde(with = "serde_eth::eth_types")]
+//!     address: ethereum_types::H160,
+//!     #[serde(with = "serde_eth::eth_types")]
+//!     bloom: ethereum_types::Bloom,
+//! }
+//! ```
+//!
+//! The encoding does not depend on how the types implement serde. Every
+//! value is written as its big endian bytes padded on the left with zeros
+//! to a number of words: `H160` is an `address`, `H256` a `bytes32`,
+//! `U128` and `U256` are `uint128` and `uint256`, and `H512`, `U512` and
+//! `Bloom`, which do not fit in a word, are static tuples of words.
+//!
+//! Human readable formats store hashes as `0x` prefixed hex strings and
+//! integers as hex quantities, like the serde implementations of the
+//! types themselves.
+
+use serde::{de, ser, ser::SerializeTuple};
+
+use alloc::{string::String, vec::Vec};
+use core::{fmt, marker::PhantomData};
+
+use super::{hex_bytes, hex_quantity, FixedBytes};
+
+/// Words is implemented by the types that are encoded as their big
+/// endian bytes in a fixed number of words
+pub trait Words: Sized {
+    /// LEN is the length in bytes of the value
+    const LEN: usize;
+
+    /// to_be_bytes returns the `LEN` bytes of the value
+    fn to_be_bytes(&self) -> Vec<u8>;
+
+    /// from_be_bytes creates the value from its `LEN` bytes
+    fn from_be_bytes(bytes: &[u8]) -> Self;
+
+    /// to_hex returns the human readable representation of the value
+    fn to_hex(&self) -> String;
+
+    /// from_hex parses the human readable representation of the value
+    fn from_hex(s: &str) -> Result<Self, String>;
+}
+
+macro_rules! impl_hash_words {
+    ( $($t:ty),+ ) => {
+        $(
+            impl Words for $t {
+                const LEN: usize = <$t>::len_bytes();
+
+                fn to_be_bytes(&self) -> Vec<u8> {
+                    self.as_bytes().to_vec()
+                }
+
+                fn from_be_bytes(bytes: &[u8]) -> Self {
+                    <$t>::from_slice(bytes)
+                }
+
+                fn to_hex(&self) -> String {
+                    hex_bytes::encode(self)
+                }
+
+                fn from_hex(s: &str) -> Result<Self, String> {
+                    let bytes = hex_bytes::decode(s)?;
+                    if bytes.len() != Self::LEN {
+                        return Err(format!("expected {} bytes", Self::LEN));
+                    }
+                    Ok(Self::from_be_bytes(&bytes))
+                }
+            }
+        )*
+    }
+}
+
+macro_rules! impl_uint_words {
+    ( $($t:ty => $len:expr),+ ) => {
+        $(
+            impl Words for $t {
+                const LEN: usize = $len;
+
+                fn to_be_bytes(&self) -> Vec<u8> {
+                    let mut bytes = vec![0u8; $len];
+                    self.to_big_endian(&mut bytes);
+                    bytes
+                }
+
+                fn from_be_bytes(bytes: &[u8]) -> Self {
+                    <$t>::from_big_endian(bytes)
+                }
+
+                fn to_hex(&self) -> String {
+                    hex_quantity::encode(self)
+                }
+
+                fn from_hex(s: &str) -> Result<Self, String> {
+                    hex_quantity::decode(s)
+                }
+            }
+        )*
+    }
+}
+
+impl_hash_words!(
+    primitive_types::H160,
+    primitive_types::H256,
+    primitive_types::H512
+);
+
+impl_uint_words!(
+    primitive_types::U128 => 16,
+    primitive_types::U256 => 32,
+    primitive_types::U512 => 64
+);
+
+#[cfg(feature = "ethereum-types")]
+impl_hash_words!(ethereum_types::Bloom);
+
+/// words returns the number of words the encoding of `T` takes
+fn words<T: Words>() -> usize {
+    T::LEN.div_ceil(32)
+}
+
+pub fn serialize<T: Words, S: ser::Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        return serializer.serialize_str(&value.to_hex());
+    }
+
+    let mut bytes = vec![0u8; words::<T>() * 32];
+    let start = bytes.len() - T::LEN;
+    bytes[start..].copy_from_slice(&value.to_be_bytes());
+
+    let mut chunks = bytes.chunks_exact(32).map(|chunk| {
+        let mut word = FixedBytes::<32>::zero();
+        word.0.copy_from_slice(chunk);
+        word
+    });
+
+    if words::<T>() == 1 {
+        return ser::Serialize::serialize(&chunks.next().unwrap_or_default(), serializer);
+    }
+
+    let mut tuple = serializer.serialize_tuple(words::<T>())?;
+    for word in chunks {
+        tuple.serialize_element(&word)?;
+    }
+    tuple.end()
+}
+
+pub fn deserialize<'de, T: Words, D: de::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    if deserializer.is_human_readable() {
+        return deserializer.deserialize_str(WordsVisitor(PhantomData));
+    }
+
+    if words::<T>() == 1 {
+        let word: FixedBytes<32> = de::Deserialize::deserialize(deserializer)?;
+        return from_padded(&word.0);
+    }
+
+    deserializer.deserialize_tuple(words::<T>(), WordsVisitor(PhantomData))
+}
+
+/// from_padded creates the value from its bytes padded on the left
+fn from_padded<T: Words, E: de::Error>(bytes: &[u8]) -> Result<T, E> {
+    let start = bytes.len() - T::LEN;
+    if bytes[..start].iter().any(|b| *b != 0) {
+        return Err(E::invalid_value(
+            de::Unexpected::Bytes(bytes),
+            &"bytes padded on the left with zeros",
+        ));
+    }
+    Ok(T::from_be_bytes(&bytes[start..]))
+}
+
+struct WordsVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Words> de::Visitor<'de> for WordsVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} words", words::<T>())
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        T::from_hex(value).map_err(E::custom)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let mut bytes = Vec::with_capacity(words::<T>() * 32);
+        for i in 0..words::<T>() {
+            let word: FixedBytes<32> = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+            bytes.extend_from_slice(&word.0);
+        }
+        from_padded(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use primitive_types::{H160, H256, H512, U128, U256, U512};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Values {
+        #[serde(with = "super")]
+        address: H160,
+        #[serde(with = "super")]
+        hash: H256,
+        #[serde(with = "super")]
+        small: U128,
+        #[serde(with = "super")]
+        amount: U256,
+        #[serde(with = "super")]
+        key: H512,
+        #[serde(with = "super")]
+        big: U512,
+        name: String,
+    }
+
+    fn values() -> Values {
+        Values {
+            address: H160::repeat_byte(0x11),
+            hash: H256::repeat_byte(0x22),
+            small: U128::from(3u64),
+            amount: U256::MAX,
+            key: H512::repeat_byte(0x33),
+            big: U512::from(4u64) << 256,
+            name: "abc".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_abi() {
+        let value = values();
+        let encoded = crate::to_string(&value).unwrap();
+        let expected = [
+            format!("{:064x}", 0x20),
+            format!("{:0>64}", "11".repeat(20)),
+            "22".repeat(32),
+            format!("{:064x}", 3),
+            "f".repeat(64),
+            "33".repeat(64),
+            format!("{:064x}{:064x}", 4, 0),
+            format!("{:064x}", 0x120),
+            format!("{:064x}", 3),
+            format!("{:0<64}", "616263"),
+        ]
+        .concat();
+        assert_eq!(encoded, expected);
+        assert_eq!(crate::from_str::<Values>(&encoded).unwrap(), value);
+    }
+
+    #[test]
+    fn test_padding() {
+        #[derive(Deserialize, Debug)]
+        struct Address(#[serde(with = "super")] H160);
+        #[derive(Deserialize, Debug)]
+        struct Small(#[serde(with = "super")] U128);
+
+        let word = format!("01{:0>62}", "11".repeat(20));
+        assert!(crate::from_str::<Address>(&word).is_err());
+        assert!(crate::from_str::<Small>(&word).is_err());
+
+        let word = format!("{:0>64}", "11".repeat(20));
+        assert_eq!(
+            crate::from_str::<Address>(&word).unwrap().0,
+            H160::repeat_byte(0x11)
+        );
+        assert!(crate::from_str::<Small>(&word).is_err());
+
+        let word = format!("{:064x}", u128::MAX);
+        assert_eq!(crate::from_str::<Small>(&word).unwrap().0, U128::MAX);
+    }
+
+    #[test]
+    fn test_json() {
+        let value = values();
+        let json = serde_json::to_value(&value).unwrap();
+        assert_eq!(json["address"], format!("0x{}", "11".repeat(20)));
+        assert_eq!(json["small"], "0x3");
+        assert_eq!(json["amount"], format!("0x{}", "f".repeat(64)));
+        assert_eq!(serde_json::from_value::<Values>(json).unwrap(), value);
+    }
+
+    #[test]
+    #[cfg(feature = "ethereum-types")]
+    fn test_bloom() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Log(#[serde(with = "super")] ethereum_types::Bloom);
+
+        let value = Log(ethereum_types::Bloom::repeat_byte(0x44));
+        let encoded = crate::to_string(&value).unwrap();
+        assert_eq!(encoded, "44".repeat(256));
+        assert_eq!(crate::from_str::<Log>(&encoded).unwrap(), value);
+    }
+}
@@ -0,0 +1,264 @@
+//! Conversions between `ethabi` tokens and the eth abi encoding, so values
+//! encoded by this crate can be decoded into a `Token` tree and tokens can
+//! be decoded into typed values.
+//!
+//! ```ignore
+//! let s = serde_eth::to_string_params(&(7u64, "abc"))?;
+//! let tokens = serde_eth::ethabi_token::decode(&[ParamType::Uint(64), ParamType::String], &s)?;
+//! let value: (u64, String) = serde_eth::from_str_params(&serde_eth::ethabi_token::encode(&tokens)?)?;
+//! ```
+
+use ethabi::{Address as H160, ParamType, Token, Uint};
+use serde::{
+    de::{self, Deserialize},
+    ser::{self, Serialize, SerializeTuple},
+};
+
+use std::io::Cursor;
+
+use super::{
+    de::{from_reader_seed, DeserializerOptions},
+    error::Result,
+    hex_bytes,
+    ser::to_string_params,
+    tokens::{self, Elements},
+    Address, FixedBytes,
+};
+
+/// TokenRef serializes a token as the value of its eth abi type
+pub struct TokenRef<'a>(pub &'a Token);
+
+impl<'a> Serialize for TokenRef<'a> {
+    fn serialize<S: ser::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        match self.0 {
+            Token::Address(address) => Address::from(address.0).serialize(serializer),
+            Token::FixedBytes(bytes) => tokens::serialize_fixed_bytes(bytes, serializer),
+            Token::Bytes(bytes) => serializer.serialize_bytes(bytes),
+            Token::Int(value) | Token::Uint(value) => {
+                let mut word = [0u8; 32];
+                value.to_big_endian(&mut word);
+                tokens::serialize_word(word, serializer)
+            }
+            Token::Bool(value) => serializer.serialize_bool(*value),
+            Token::String(value) => serializer.serialize_str(value),
+            Token::Array(tokens) => serializer.collect_seq(tokens.iter().map(TokenRef)),
+            Token::FixedArray(tokens) | Token::Tuple(tokens) => {
+                TokensRef(tokens).serialize(serializer)
+            }
+        }
+    }
+}
+
+/// TokensRef serializes tokens as a tuple
+struct TokensRef<'a>(&'a [Token]);
+
+impl<'a> Serialize for TokensRef<'a> {
+    fn serialize<S: ser::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(self.0.len())?;
+        for token in self.0 {
+            tuple.serialize_element(&TokenRef(token))?;
+        }
+        tuple.end()
+    }
+}
+
+/// TokenSeed deserializes a token of the given type
+#[derive(Clone, Copy)]
+pub struct TokenSeed<'a>(pub &'a ParamType);
+
+impl<'de, 'a> de::DeserializeSeed<'de> for TokenSeed<'a> {
+    type Value = Token;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> core::result::Result<Token, D::Error> {
+        Ok(match self.0 {
+            ParamType::Address => {
+                let address = Address::deserialize(deserializer)?;
+                Token::Address(H160::from(<[u8; 20]>::from(address)))
+            }
+            ParamType::Bytes => Token::Bytes(hex_bytes::deserialize(deserializer)?),
+            ParamType::Int(bits) => {
+                let word = tokens::int_word(FixedBytes::deserialize(deserializer)?, *bits)?;
+                Token::Int(Uint::from_big_endian(&word))
+            }
+            ParamType::Uint(bits) => {
+                let word = tokens::uint_word(FixedBytes::deserialize(deserializer)?, *bits)?;
+                Token::Uint(Uint::from_big_endian(&word))
+            }
+            ParamType::Bool => Token::Bool(bool::deserialize(deserializer)?),
+            ParamType::String => Token::String(String::deserialize(deserializer)?),
+            ParamType::Array(kind) => Token::Array(deserializer.deserialize_seq(Elements {
+                seeds: Vec::new(),
+                element: Some(TokenSeed(kind)),
+            })?),
+            ParamType::FixedBytes(len) => {
+                let word = tokens::fixed_bytes_word(FixedBytes::deserialize(deserializer)?, *len)?;
+                Token::FixedBytes(word[..*len].to_vec())
+            }
+            ParamType::FixedArray(kind, len) => Token::FixedArray(tokens::deserialize_tuple(
+                deserializer,
+                *len,
+                self.0.is_dynamic(),
+                Elements {
+                    seeds: vec![TokenSeed(kind); *len],
+                    element: None,
+                },
+            )?),
+            ParamType::Tuple(kinds) => Token::Tuple(tokens::deserialize_tuple(
+                deserializer,
+                kinds.len(),
+                self.0.is_dynamic(),
+                Elements {
+                    seeds: kinds.iter().map(TokenSeed).collect(),
+                    element: None,
+                },
+            )?),
+        })
+    }
+}
+
+/// encode encodes tokens as the list of parameters of a function call,
+/// like `ethabi::encode` but as a hex string
+pub fn encode(tokens: &[Token]) -> Result<String> {
+    to_string_params(&TokensRef(tokens))
+}
+
+/// decode decodes the list of parameters of a function call into tokens
+/// of the given types, like `ethabi::decode` does with the binary encoding
+pub fn decode(types: &[ParamType], s: &str) -> Result<Vec<Token>> {
+    let kind = ParamType::Tuple(types.to_vec());
+    let options = DeserializerOptions::new().params(true);
+    match from_reader_seed(Cursor::new(s), TokenSeed(&kind), options)? {
+        Token::Tuple(tokens) => Ok(tokens),
+        _ => unreachable!("a tuple type decodes into a tuple token"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{decode, encode, TokenRef, TokenSeed};
+    use crate::{
+        corpus_tests::Kind,
+        de::{from_reader_seed, DeserializerOptions},
+        from_str_params, from_str_seed, to_string, to_string_params, Address,
+    };
+    use ethabi::{ParamType, Token};
+    use std::io::Cursor;
+
+    fn param_type(kind: &Kind) -> ParamType {
+        match kind {
+            Kind::Uint(bits) => ParamType::Uint(*bits),
+            Kind::Int(bits) => ParamType::Int(*bits),
+            Kind::Bool => ParamType::Bool,
+            Kind::String => ParamType::String,
+            Kind::Bytes => ParamType::Bytes,
+            Kind::FixedBytes(len) => ParamType::FixedBytes(*len),
+            Kind::Address => ParamType::Address,
+            Kind::Array(kind) => ParamType::Array(Box::new(param_type(kind))),
+            Kind::FixedArray(kind, len) => ParamType::FixedArray(Box::new(param_type(kind)), *len),
+            Kind::Tuple(kinds) => ParamType::Tuple(kinds.iter().map(param_type).collect()),
+        }
+    }
+
+    #[test]
+    fn test_corpus() {
+        let corpus: serde_json::Value =
+            serde_json::from_str(include_str!("../testdata/corpus.json")).unwrap();
+
+        for case in corpus.as_array().unwrap() {
+            let ty = case["type"].as_str().unwrap();
+            let encoded = case["encoded"].as_str().unwrap();
+            let kind = param_type(&Kind::parse(ty));
+
+            let token = from_str_seed(encoded, TokenSeed(&kind))
+                .unwrap_or_else(|err| panic!("decoding {}: {}", ty, err));
+            let expected = ethabi::decode(&[kind], &hex::decode(encoded).unwrap()).unwrap();
+            assert_eq!(vec![token.clone()], expected, "decoding {}", ty);
+            assert_eq!(
+                to_string(&TokenRef(&token)).unwrap(),
+                encoded,
+                "encoding {}",
+                ty
+            );
+        }
+    }
+
+    #[test]
+    fn test_params() {
+        let value = (
+            Address::from([0x11; 20]),
+            7u64,
+            -2i32,
+            vec![("abc".to_string(), 1u8), (String::new(), 2u8)],
+            ["x".to_string(), "yz".to_string()],
+        );
+        let types = [
+            ParamType::Address,
+            ParamType::Uint(64),
+            ParamType::Int(32),
+            ParamType::Array(Box::new(ParamType::Tuple(vec![
+                ParamType::String,
+                ParamType::Uint(8),
+            ]))),
+            ParamType::FixedArray(Box::new(ParamType::String), 2),
+        ];
+
+        let s = to_string_params(&value).unwrap();
+        let tokens = decode(&types, &s).unwrap();
+        let expected = ethabi::decode(&types, &hex::decode(&s).unwrap()).unwrap();
+        assert_eq!(tokens, expected);
+
+        assert_eq!(encode(&tokens).unwrap(), s);
+        assert_eq!(hex::encode(ethabi::encode(&tokens)), s);
+        assert_eq!(
+            from_str_params::<(Address, u64, i32, Vec<(String, u8)>, [String; 2])>(&s).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn test_decode_error() {
+        let word = |s: &str| format!("{:0>64}", s);
+
+        assert!(decode(&[ParamType::Uint(8)], &word("ff")).is_ok());
+        assert!(decode(&[ParamType::Uint(8)], &word("100")).is_err());
+        assert!(decode(&[ParamType::Int(8)], &word("80")).is_err());
+        assert!(decode(&[ParamType::Int(8)], &format!("{:f>64}", "80")).is_ok());
+        assert!(decode(&[ParamType::FixedBytes(1)], &word("01")).is_err());
+
+        let tokens = [Token::FixedBytes(vec![0; 33])];
+        assert!(encode(&tokens).is_err());
+    }
+
+    #[test]
+    fn test_decode_static_tuples_like_offsets() {
+        // the first field of every tuple looks like an offset, but the
+        // type tells that the tuples are static, so nothing is guessed
+        let value: Vec<(u64, u64)> = (0..200).map(|i| (0x2000, i)).collect();
+        let s = to_string_params(&(value,)).unwrap();
+        let kinds = vec![ParamType::Array(Box::new(ParamType::Tuple(vec![
+            ParamType::Uint(64),
+            ParamType::Uint(64),
+        ])))];
+
+        let tokens = decode(&kinds, &s).unwrap();
+        assert_eq!(
+            tokens,
+            ethabi::decode(&kinds, &hex::decode(&s).unwrap()).unwrap()
+        );
+
+        let params = ParamType::Tuple(kinds);
+        let options = DeserializerOptions::new().params(true).max_retries(0);
+        let token = from_reader_seed(Cursor::new(&s), TokenSeed(&params), options).unwrap();
+        assert_eq!(token, Token::Tuple(tokens));
+    }
+}
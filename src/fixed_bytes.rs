@@ -0,0 +1,166 @@
+use serde::{de, ser};
+
+use core::fmt;
+
+use super::hex_bytes;
+
+/// FixedBytes is a byte array of `N` bytes, the `bytesN` type of the
+/// eth abi, for `N` between 1 and 32.
+///
+/// When serialized with a human readable format the bytes are written
+/// as a `0x` prefixed hex string. Otherwise they are serialized with
+/// the same shape as oasis-std `H256`, padded on the right with zeros,
+/// so the eth abi serializer encodes them as a `bytesN`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FixedBytes<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> FixedBytes<N> {
+    /// zero returns the bytes with all of them set to zero
+    pub fn zero() -> Self {
+        FixedBytes([0u8; N])
+    }
+}
+
+impl<const N: usize> Default for FixedBytes<N> {
+    fn default() -> Self {
+        FixedBytes::zero()
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for FixedBytes<N> {
+    fn from(bytes: [u8; N]) -> Self {
+        FixedBytes(bytes)
+    }
+}
+
+impl<const N: usize> From<FixedBytes<N>> for [u8; N] {
+    fn from(bytes: FixedBytes<N>) -> Self {
+        bytes.0
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for FixedBytes<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedBytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "FixedBytes({})", hex_bytes::encode(self))
+    }
+}
+
+impl<const N: usize> ser::Serialize for FixedBytes<N> {
+    fn serialize<S: ser::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return serializer.serialize_str(&hex_bytes::encode(self));
+        }
+
+        if N == 0 || N > 32 {
+            return Err(ser::Error::custom(
+                "fixed bytes must be between 1 and 32 bytes long",
+            ));
+        }
+
+        let mut word = [0u8; 32];
+        word[..N].copy_from_slice(&self.0);
+        serializer.serialize_newtype_struct("H256", &word)
+    }
+}
+
+struct FixedBytesVisitor<const N: usize>;
+
+impl<const N: usize> FixedBytesVisitor<N> {
+    fn decode_word<E: de::Error>(&self, word: [u8; 32]) -> core::result::Result<FixedBytes<N>, E> {
+        if N == 0 || N > 32 {
+            return Err(E::custom("fixed bytes must be between 1 and 32 bytes long"));
+        }
+
+        if word[N..].iter().any(|b| *b != 0) {
+            return Err(E::invalid_value(
+                de::Unexpected::Bytes(&word),
+                &"bytes padded on the right with zeros",
+            ));
+        }
+
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(&word[..N]);
+        Ok(FixedBytes(bytes))
+    }
+}
+
+impl<'de, const N: usize> de::Visitor<'de> for FixedBytesVisitor<N> {
+    type Value = FixedBytes<N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} bytes", N)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> core::result::Result<FixedBytes<N>, E> {
+        let decoded = hex_bytes::decode(value).map_err(E::custom)?;
+        if decoded.len() != N {
+            return Err(E::invalid_length(decoded.len(), &self));
+        }
+
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(&decoded);
+        Ok(FixedBytes(bytes))
+    }
+
+    fn visit_newtype_struct<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> core::result::Result<FixedBytes<N>, D::Error> {
+        let word: [u8; 32] = de::Deserialize::deserialize(deserializer)?;
+        self.decode_word(word)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> core::result::Result<FixedBytes<N>, A::Error> {
+        let word: [u8; 32] = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        self.decode_word(word)
+    }
+}
+
+impl<'de, const N: usize> de::Deserialize<'de> for FixedBytes<N> {
+    fn deserialize<D: de::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(FixedBytesVisitor)
+        } else {
+            deserializer.deserialize_newtype_struct("H256", FixedBytesVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::FixedBytes;
+
+    #[test]
+    fn test_fixed_bytes_json() {
+        let bytes = FixedBytes(*b"abc");
+        let json = serde_json::to_string(&bytes).unwrap();
+        assert_eq!(json, "\"0x616263\"");
+        assert_eq!(serde_json::from_str::<FixedBytes<3>>(&json).unwrap(), bytes);
+        assert!(serde_json::from_str::<FixedBytes<4>>(&json).is_err());
+    }
+
+    #[test]
+    fn test_fixed_bytes_padding() {
+        let word = "6162630000000000000000000000000000000000000000000000000000000001";
+        assert!(crate::from_str::<FixedBytes<3>>(word).is_err());
+        assert!(crate::from_str::<FixedBytes<32>>(word).is_ok());
+        assert!(crate::to_string(&FixedBytes([0u8; 33])).is_err());
+    }
+}
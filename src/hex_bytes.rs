@@ -0,0 +1,126 @@
+//! Serialize byte arrays as `0x` prefixed hex strings, the way ethereum
+//! JSON-RPC represents unformatted data. To be used with
+//! `#[serde(with = ...)]`:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Call {
+//!     #[serde(with = "serde_eth::hex_bytes")]
+//!     data: Vec<u8>,
+//! }
+//! ```
+//!
+//! Formats that are not human readable, like the eth abi serializer,
+//! serialize the value as `bytes`.
+
+use serde::{de, ser};
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+/// encode returns the `0x` prefixed hex representation of the bytes
+pub fn encode<T: AsRef<[u8]>>(value: T) -> String {
+    format!("0x{}", hex::encode(value.as_ref()))
+}
+
+/// decode parses a `0x` prefixed hex string
+pub fn decode(s: &str) -> Result<Vec<u8>, String> {
+    if !s.starts_with("0x") {
+        return Err("hex bytes are missing 0x prefix".to_string());
+    }
+
+    hex::decode(&s[2..]).map_err(|err| err.to_string())
+}
+
+pub fn serialize<T: AsRef<[u8]>, S: ser::Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&encode(value))
+    } else {
+        serializer.serialize_bytes(value.as_ref())
+    }
+}
+
+pub fn deserialize<'de, T: From<Vec<u8>>, D: de::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(BytesVisitor).map(T::from)
+    } else {
+        deserializer.deserialize_byte_buf(BytesVisitor).map(T::from)
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> de::Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("0x prefixed hex bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Vec<u8>, E> {
+        decode(value).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Vec<u8>, E> {
+        Ok(value.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Vec<u8>, E> {
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use alloc::vec::Vec;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Call {
+        #[serde(with = "super")]
+        data: Vec<u8>,
+    }
+
+    #[test]
+    fn test_json() {
+        let tests = &[
+            (vec![], r#"{"data":"0x"}"#),
+            (vec![0x00, 0x01, 0xab], r#"{"data":"0x0001ab"}"#),
+        ];
+
+        for (data, json) in tests {
+            let call = Call { data: data.clone() };
+            assert_eq!(serde_json::to_string(&call).unwrap(), *json);
+            assert_eq!(serde_json::from_str::<Call>(json).unwrap(), call);
+        }
+
+        assert!(serde_json::from_str::<Call>(r#"{"data":"0001ab"}"#).is_err());
+        assert!(serde_json::from_str::<Call>(r#"{"data":"0x0"}"#).is_err());
+    }
+
+    #[test]
+    fn test_abi() {
+        let call = Call {
+            data: b"hello".to_vec(),
+        };
+
+        let encoded = crate::to_string(&call).unwrap();
+        assert_eq!(
+            encoded,
+            "0000000000000000000000000000000000000000000000000000000000000020\
+             0000000000000000000000000000000000000000000000000000000000000020\
+             0000000000000000000000000000000000000000000000000000000000000005\
+             68656c6c6f000000000000000000000000000000000000000000000000000000"
+        );
+        assert_eq!(crate::from_str::<Call>(&encoded).unwrap(), call);
+    }
+}
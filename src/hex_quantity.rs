@@ -0,0 +1,256 @@
+//! Serialize integers as ethereum JSON-RPC quantities, i.e. `0x` prefixed
+//! hex strings without leading zeros. To be used with `#[serde(with = ...)]`:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Transaction {
+//!     #[serde(with = "serde_eth::hex_quantity")]
+//!     nonce: u64,
+//! }
+//! ```
+//!
+//! Formats that are not human readable, like the eth abi serializer,
+//! serialize the integer with its regular representation.
+
+use serde::{de, ser};
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+/// Quantity is implemented by the integer types that can be
+/// represented as a hex quantity
+pub trait Quantity: Sized {
+    /// to_be_bytes returns the big endian representation of the integer
+    fn to_be_bytes(&self) -> Vec<u8>;
+
+    /// from_be_bytes creates the integer from its big endian representation.
+    /// It returns None if the value does not fit in the integer
+    fn from_be_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+macro_rules! impl_quantity {
+    ( $($t:ty),+ ) => {
+        $(
+            impl Quantity for $t {
+                fn to_be_bytes(&self) -> Vec<u8> {
+                    <$t>::to_be_bytes(*self).to_vec()
+                }
+
+                fn from_be_bytes(bytes: &[u8]) -> Option<Self> {
+                    let size = core::mem::size_of::<$t>();
+                    let start = bytes.len().saturating_sub(size);
+                    if bytes[..start].iter().any(|b| *b != 0) {
+                        return None;
+                    }
+
+                    let mut buf = [0u8; core::mem::size_of::<$t>()];
+                    buf[size - (bytes.len() - start)..].copy_from_slice(&bytes[start..]);
+                    Some(<$t>::from_be_bytes(buf))
+                }
+            }
+        )*
+    }
+}
+
+impl_quantity!(u8, u16, u32, u64, u128, usize);
+
+#[cfg(feature = "oasis-std")]
+impl Quantity for oasis_std::types::U256 {
+    fn to_be_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; 32];
+        self.to_big_endian(&mut bytes);
+        bytes
+    }
+
+    fn from_be_bytes(bytes: &[u8]) -> Option<Self> {
+        let start = bytes.len().saturating_sub(32);
+        if bytes[..start].iter().any(|b| *b != 0) {
+            return None;
+        }
+
+        Some(oasis_std::types::U256::from_big_endian(&bytes[start..]))
+    }
+}
+
+#[cfg(feature = "primitive-types")]
+macro_rules! impl_uint_quantity {
+    ( $($t:ty => $len:expr),+ ) => {
+        $(
+            impl Quantity for $t {
+                fn to_be_bytes(&self) -> Vec<u8> {
+                    let mut bytes = vec![0u8; $len];
+                    self.to_big_endian(&mut bytes);
+                    bytes
+                }
+
+                fn from_be_bytes(bytes: &[u8]) -> Option<Self> {
+                    let start = bytes.len().saturating_sub($len);
+                    if bytes[..start].iter().any(|b| *b != 0) {
+                        return None;
+                    }
+
+                    Some(<$t>::from_big_endian(&bytes[start..]))
+                }
+            }
+        )*
+    }
+}
+
+#[cfg(feature = "primitive-types")]
+impl_uint_quantity!(
+    primitive_types::U128 => 16,
+    primitive_types::U256 => 32,
+    primitive_types::U512 => 64
+);
+
+/// encode returns the hex quantity representation of the value
+pub fn encode<T: Quantity>(value: &T) -> String {
+    let encoded = hex::encode(value.to_be_bytes());
+    let trimmed = encoded.trim_start_matches('0');
+    if trimmed.is_empty() {
+        "0x0".to_string()
+    } else {
+        format!("0x{}", trimmed)
+    }
+}
+
+/// decode parses a hex quantity. The `0x` prefix is required, and the
+/// quantity must be compact: zero is `0x0`, and other values have no
+/// leading zero digits
+pub fn decode<T: Quantity>(s: &str) -> Result<T, String> {
+    if !s.starts_with("0x") {
+        return Err("hex quantity is missing 0x prefix".to_string());
+    }
+
+    let digits = &s[2..];
+    if digits.is_empty() {
+        return Err("hex quantity has no digits".to_string());
+    }
+    if digits.len() > 1 && digits.starts_with('0') {
+        return Err("hex quantity has leading zeros".to_string());
+    }
+
+    // hex decoding works on full bytes so odd number of digits
+    // need to be padded
+    let padded = if digits.len().is_multiple_of(2) {
+        digits.to_string()
+    } else {
+        format!("0{}", digits)
+    };
+
+    let bytes = hex::decode(&padded).map_err(|err| err.to_string())?;
+    T::from_be_bytes(&bytes).ok_or_else(|| "hex quantity overflows integer".to_string())
+}
+
+pub fn serialize<T: Quantity + ser::Serialize, S: ser::Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&encode(value))
+    } else {
+        value.serialize(serializer)
+    }
+}
+
+pub fn deserialize<'de, T: Quantity + de::Deserialize<'de>, D: de::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(QuantityVisitor(core::marker::PhantomData))
+    } else {
+        T::deserialize(deserializer)
+    }
+}
+
+struct QuantityVisitor<T>(core::marker::PhantomData<T>);
+
+impl<'de, T: Quantity> de::Visitor<'de> for QuantityVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a 0x prefixed hex quantity")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        decode(value).map_err(E::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{decode, encode};
+
+    #[cfg(feature = "oasis-std")]
+    use oasis_std::types::U256;
+    #[cfg(feature = "oasis-std")]
+    use serde::{Deserialize, Serialize};
+
+    #[cfg(feature = "oasis-std")]
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Quantities {
+        #[serde(with = "super")]
+        small: u8,
+        #[serde(with = "super")]
+        nonce: u64,
+        #[serde(with = "super")]
+        value: U256,
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let tests: &[(u64, &str)] = &[(0, "0x0"), (1, "0x1"), (0x41, "0x41"), (0x400, "0x400")];
+
+        for (value, s) in tests {
+            assert_eq!(encode(value), *s);
+            assert_eq!(decode::<u64>(s).unwrap(), *value);
+        }
+
+        assert!(decode::<u64>("0x0400").is_err());
+        assert!(decode::<u64>("0x00").is_err());
+        assert!(decode::<u64>("0x").is_err());
+        assert!(decode::<u64>("400").is_err());
+        assert!(decode::<u64>("0xz").is_err());
+        assert!(decode::<u8>("0x100").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "oasis-std")]
+    fn test_json() {
+        let value = Quantities {
+            small: 0xff,
+            nonce: 0,
+            value: U256::from(1_000_000_000u64),
+        };
+
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(
+            json,
+            r#"{"small":"0xff","nonce":"0x0","value":"0x3b9aca00"}"#
+        );
+        assert_eq!(serde_json::from_str::<Quantities>(&json).unwrap(), value);
+    }
+
+    #[test]
+    #[cfg(feature = "oasis-std")]
+    fn test_abi() {
+        let value = Quantities {
+            small: 1,
+            nonce: 2,
+            value: U256::from(3u64),
+        };
+
+        let encoded = crate::to_string(&value).unwrap();
+        assert_eq!(
+            encoded,
+            "0000000000000000000000000000000000000000000000000000000000000001\
+             0000000000000000000000000000000000000000000000000000000000000002\
+             0000000000000000000000000000000000000000000000000000000000000003"
+        );
+        assert_eq!(crate::from_str::<Quantities>(&encoded).unwrap(), value);
+    }
+}
@@ -0,0 +1,175 @@
+//! The io traits the serializer and the deserializer are built on. With
+//! the `std` feature they are the ones of `std::io`. Without it, this
+//! module provides the subset of `std::io` the crate needs, implemented
+//! for `Vec<u8>` and for a `Cursor` over a byte slice or a string, so
+//! that values can be encoded to a vector and decoded from a slice in
+//! `no_std` environments such as wasm contracts.
+
+#[cfg(feature = "std")]
+pub use std::io::{Cursor, Error, Read, Result, Seek, SeekFrom, Write};
+
+#[cfg(not(feature = "std"))]
+pub use self::core_io::{Cursor, Error, Read, Result, Seek, SeekFrom, Write};
+
+#[cfg(not(feature = "std"))]
+mod core_io {
+
+    use alloc::vec::Vec;
+    use core::{cmp, fmt, result};
+
+    /// Error is returned by the io operations that cannot succeed, like
+    /// seeking before the start of a cursor
+    #[derive(Debug)]
+    pub struct Error(&'static str);
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    pub type Result<T> = result::Result<T, Error>;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SeekFrom {
+        Start(u64),
+        End(i64),
+        Current(i64),
+    }
+
+    pub trait Read {
+        /// read reads into `buf`, returning how many bytes were read.
+        /// It returns 0 at the end of the input
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+    }
+
+    pub trait Seek {
+        /// seek moves to a position, returning the new offset from the
+        /// start
+        fn seek(&mut self, pos: SeekFrom) -> Result<u64>;
+
+        /// stream_position returns the current offset from the start
+        fn stream_position(&mut self) -> Result<u64> {
+            self.seek(SeekFrom::Current(0))
+        }
+    }
+
+    pub trait Write {
+        /// write writes some bytes of `buf`, returning how many were
+        /// written
+        fn write(&mut self, buf: &[u8]) -> Result<usize>;
+
+        fn flush(&mut self) -> Result<()>;
+
+        /// write_all writes the whole of `buf`
+        fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.write(buf)? {
+                    0 => return Err(Error("failed to write whole buffer")),
+                    n => buf = &buf[n..],
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl<R: Read + ?Sized> Read for &mut R {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            (**self).read(buf)
+        }
+    }
+
+    impl<S: Seek + ?Sized> Seek for &mut S {
+        fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+            (**self).seek(pos)
+        }
+    }
+
+    impl<W: Write + ?Sized> Write for &mut W {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            (**self).write(buf)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            (**self).flush()
+        }
+    }
+
+    impl Write for Vec<u8> {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Cursor reads from the bytes of a slice, a vector or a string
+    #[derive(Debug, Clone, Default)]
+    pub struct Cursor<T> {
+        inner: T,
+        pos: u64,
+    }
+
+    impl<T> Cursor<T> {
+        pub fn new(inner: T) -> Self {
+            Cursor { inner, pos: 0 }
+        }
+
+        pub fn into_inner(self) -> T {
+            self.inner
+        }
+
+        pub fn get_ref(&self) -> &T {
+            &self.inner
+        }
+
+        pub fn position(&self) -> u64 {
+            self.pos
+        }
+
+        pub fn set_position(&mut self, pos: u64) {
+            self.pos = pos;
+        }
+    }
+
+    impl<T: AsRef<[u8]>> Read for Cursor<T> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let bytes = self.inner.as_ref();
+            let start = cmp::min(self.pos, bytes.len() as u64) as usize;
+            let len = cmp::min(buf.len(), bytes.len() - start);
+            buf[..len].copy_from_slice(&bytes[start..start + len]);
+            self.pos += len as u64;
+            Ok(len)
+        }
+    }
+
+    impl<T: AsRef<[u8]>> Seek for Cursor<T> {
+        fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+            let (base, offset) = match pos {
+                SeekFrom::Start(offset) => {
+                    self.pos = offset;
+                    return Ok(offset);
+                }
+                SeekFrom::End(offset) => (self.inner.as_ref().len() as u64, offset),
+                SeekFrom::Current(offset) => (self.pos, offset),
+            };
+
+            let pos = if offset >= 0 {
+                base.checked_add(offset as u64)
+            } else {
+                base.checked_sub(offset.unsigned_abs())
+            };
+
+            match pos {
+                Some(pos) => {
+                    self.pos = pos;
+                    Ok(pos)
+                }
+                None => Err(Error("invalid seek to a negative or overflowing position")),
+            }
+        }
+    }
+}
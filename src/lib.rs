@@ -1,11 +1,53 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[macro_use]
+extern crate alloc;
+
+// the test harness needs std, so the tests can use it without the feature
+#[cfg(all(test, not(feature = "std")))]
+extern crate std;
+
+pub mod abi_encoded;
+pub mod abi_type;
+pub mod address;
+#[cfg(feature = "alloy")]
+pub mod alloy_value;
+#[cfg(test)]
+mod corpus_tests;
 mod custom_de;
 mod custom_ser;
 pub mod de;
+pub mod diff;
+pub mod dump;
 mod error;
 mod eth;
+#[cfg(feature = "primitive-types")]
+pub mod eth_types;
+#[cfg(feature = "ethabi")]
+pub mod ethabi_token;
+mod fixed_bytes;
+pub mod hex_bytes;
+pub mod hex_quantity;
+pub mod io;
+#[cfg(test)]
+mod prop_tests;
 pub mod ser;
+#[cfg(test)]
 mod serde_tests;
+mod tokens;
+
+pub use address::Address;
+pub use error::{Error, ErrorKind, Result};
+pub use fixed_bytes::FixedBytes;
+pub use ser::{
+    encoded_len, encoded_len_params, to_string, to_string_params, to_vec, to_writer,
+    to_writer_streaming,
+};
 
-pub use ser::{to_string, to_vec, to_writer};
+pub use de::{
+    from_reader, from_reader_seed, from_reader_with_options, from_slice, from_str, from_str_params,
+    from_str_seed, from_str_with_options,
+};
 
-pub use de::{from_reader, from_str};
+#[cfg(feature = "async")]
+pub use de::{from_async_reader, from_async_reader_with_options};
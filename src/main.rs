@@ -0,0 +1,353 @@
+//! serde-eth encodes and decodes eth abi data from the command line,
+//! printing JSON. Build it with the `cli` feature:
+//!
+//! ```ignore
+//! cargo install serde-eth --features cli
+//! serde-eth encode 'transfer(address,uint256)' 0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed 1000
+//! serde-eth calldata 'transfer(address,uint256)' @calldata.hex
+//! ```
+//!
+//! Integers are printed as decimal strings, and bytes and addresses as
+//! `0x` prefixed hex strings. Arguments of array and tuple types are
+//! JSON arrays, whose elements may be strings, numbers or booleans.
+
+use serde_eth::{
+    abi_type::{decode_params, encode_params, AbiType, AbiValue, Signature},
+    diff::diff_str_params,
+    dump::dump_str_params,
+};
+use serde_json::{json, Value};
+
+use std::{
+    env, fs,
+    io::{self, Read},
+    process,
+};
+
+const USAGE: &str = "usage:
+    serde-eth encode <signature> [<args>...]
+    serde-eth decode <types> [<hex>]
+    serde-eth calldata <signature> [<hex>]
+    serde-eth selector <signature>
+    serde-eth dump <types> [<hex>]
+    serde-eth diff <types> <hex> [<hex>]
+
+A signature is a function, e.g. 'transfer(address,uint256)', or a list of
+parameter types without a name, e.g. '(address,uint256)', which encode
+without a selector. When <hex> is missing or '-' it is read from stdin, and
+'@<path>' reads it from a file. encode reads its arguments from stdin as a
+JSON array when there are none. dump prints every word of the encoding of
+a list of parameters with what it encodes, and diff prints where two
+encodings of a list of parameters first differ.";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(output) => println!("{}", output),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+/// run runs the command in `args` and returns what it prints
+fn run(args: &[String]) -> Result<String, String> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = match args[..] {
+        ["encode", signature, ref values @ ..] => encode(signature, values)?,
+        ["decode", types] => decode(types, &input(None)?)?,
+        ["decode", types, source] => decode(types, &input(Some(source))?)?,
+        ["calldata", signature] => calldata(signature, &input(None)?)?,
+        ["calldata", signature, source] => calldata(signature, &input(Some(source))?)?,
+        ["selector", signature] => selector(signature)?,
+        ["dump", types] => return dump(types, &input(None)?),
+        ["dump", types, source] => return dump(types, &input(Some(source))?),
+        ["diff", types, left] => return diff(types, &input(Some(left))?, &input(None)?),
+        ["diff", types, left, right] => {
+            return diff(types, &input(Some(left))?, &input(Some(right))?)
+        }
+        ["help"] | ["-h"] | ["--help"] => return Ok(USAGE.to_string()),
+        _ => return Err(USAGE.to_string()),
+    };
+    serde_json::to_string_pretty(&output).map_err(|err| err.to_string())
+}
+
+/// input reads the hex input from stdin, a file or the argument itself
+fn input(source: Option<&str>) -> Result<String, String> {
+    let s = match source {
+        None | Some("-") => {
+            let mut s = String::new();
+            io::stdin()
+                .read_to_string(&mut s)
+                .map_err(|err| format!("reading stdin: {}", err))?;
+            s
+        }
+        Some(source) => match source.strip_prefix('@') {
+            Some(path) => {
+                fs::read_to_string(path).map_err(|err| format!("reading {}: {}", path, err))?
+            }
+            None => source.to_string(),
+        },
+    };
+
+    let s = s.trim();
+    Ok(s.strip_prefix("0x").unwrap_or(s).to_string())
+}
+
+fn parse_signature(signature: &str) -> Result<Signature, String> {
+    Signature::parse(signature).map_err(|err| err.to_string())
+}
+
+fn encode(signature: &str, args: &[&str]) -> Result<Value, String> {
+    let signature = parse_signature(signature)?;
+    let args: Vec<Value> = if args.is_empty() && !signature.inputs.is_empty() {
+        serde_json::from_str(&input(None)?).map_err(|err| format!("reading arguments: {}", err))?
+    } else {
+        args.iter()
+            .map(|arg| Value::String(arg.to_string()))
+            .collect()
+    };
+
+    if args.len() != signature.inputs.len() {
+        return Err(format!(
+            "{} takes {} arguments, got {}",
+            signature,
+            signature.inputs.len(),
+            args.len()
+        ));
+    }
+
+    let values = signature
+        .inputs
+        .iter()
+        .zip(args.iter())
+        .map(|(kind, arg)| from_arg(kind, arg))
+        .collect::<Result<Vec<_>, _>>()?;
+    let params = encode_params(&values).map_err(|err| err.to_string())?;
+
+    let selector = if signature.name.is_empty() {
+        String::new()
+    } else {
+        hex::encode(signature.selector())
+    };
+    Ok(Value::String(format!("0x{}{}", selector, params)))
+}
+
+fn decode(types: &str, hex: &str) -> Result<Value, String> {
+    let types = AbiType::parse_list(types).map_err(|err| err.to_string())?;
+    let values = decode_params(&types, hex).map_err(|err| err.to_string())?;
+    Ok(Value::Array(values.iter().map(to_json).collect()))
+}
+
+fn calldata(signature: &str, hex: &str) -> Result<Value, String> {
+    let signature = parse_signature(signature)?;
+    let selector = hex::encode(signature.selector());
+    let params = match hex.get(..8) {
+        Some(prefix) if prefix.eq_ignore_ascii_case(&selector) => &hex[8..],
+        _ => {
+            return Err(format!(
+                "calldata does not start with the selector 0x{} of {}",
+                selector, signature
+            ))
+        }
+    };
+
+    let values = decode_params(&signature.inputs, params).map_err(|err| err.to_string())?;
+    Ok(json!({
+        "function": signature.to_string(),
+        "selector": format!("0x{}", selector),
+        "args": values.iter().map(to_json).collect::<Vec<_>>(),
+    }))
+}
+
+fn selector(signature: &str) -> Result<Value, String> {
+    let signature = parse_signature(signature)?;
+    Ok(json!({
+        "function": signature.to_string(),
+        "selector": format!("0x{}", hex::encode(signature.selector())),
+    }))
+}
+
+fn dump(types: &str, hex: &str) -> Result<String, String> {
+    let types = AbiType::parse_list(types).map_err(|err| err.to_string())?;
+    let dump = dump_str_params(&types, hex).map_err(|err| err.to_string())?;
+    Ok(dump.trim_end().to_string())
+}
+
+fn diff(types: &str, left: &str, right: &str) -> Result<String, String> {
+    let types = AbiType::parse_list(types).map_err(|err| err.to_string())?;
+    let difference = diff_str_params(&types, left, right).map_err(|err| err.to_string())?;
+    Ok(match difference {
+        Some(difference) => difference.to_string(),
+        None => "no difference".to_string(),
+    })
+}
+
+/// from_arg converts an argument into a value of the given type. The
+/// arguments of arrays and tuples are JSON arrays
+fn from_arg(kind: &AbiType, arg: &Value) -> Result<AbiValue, String> {
+    match (kind, arg) {
+        (AbiType::Array(_), Value::String(s))
+        | (AbiType::FixedArray(_, _), Value::String(s))
+        | (AbiType::Tuple(_), Value::String(s)) => {
+            let arg: Value = serde_json::from_str(s)
+                .map_err(|err| format!("invalid argument {} for {}: {}", s, kind, err))?;
+            from_json(kind, &arg)
+        }
+        _ => from_json(kind, arg),
+    }
+}
+
+/// from_json converts a JSON value into a value of the given type
+fn from_json(kind: &AbiType, value: &Value) -> Result<AbiValue, String> {
+    let elements = |kind: &AbiType, items: &[Value]| {
+        items
+            .iter()
+            .map(|item| from_json(kind, item))
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    match (kind, value) {
+        (AbiType::Array(kind), Value::Array(items)) => Ok(AbiValue::Array(elements(kind, items)?)),
+        (AbiType::FixedArray(kind, len), Value::Array(items)) if items.len() == *len => {
+            Ok(AbiValue::FixedArray(elements(kind, items)?))
+        }
+        (AbiType::Tuple(kinds), Value::Array(items)) if items.len() == kinds.len() => {
+            let values = kinds
+                .iter()
+                .zip(items.iter())
+                .map(|(kind, item)| from_json(kind, item))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(AbiValue::Tuple(values))
+        }
+        (_, Value::String(s)) => kind.parse_value(s).map_err(|err| err.to_string()),
+        (_, Value::Number(_)) | (_, Value::Bool(_)) => kind
+            .parse_value(&value.to_string())
+            .map_err(|err| err.to_string()),
+        _ => Err(format!("invalid value {} for {}", value, kind)),
+    }
+}
+
+/// to_json converts a value into JSON
+fn to_json(value: &AbiValue) -> Value {
+    match value {
+        AbiValue::Bool(value) => Value::Bool(*value),
+        AbiValue::String(value) => Value::String(value.clone()),
+        AbiValue::Array(values) | AbiValue::FixedArray(values) | AbiValue::Tuple(values) => {
+            Value::Array(values.iter().map(to_json).collect())
+        }
+        _ => Value::String(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::run;
+    use serde_json::{json, Value};
+
+    fn run_json(args: &[&str]) -> Value {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        serde_json::from_str(&run(&args).unwrap()).unwrap()
+    }
+
+    const ADDRESS: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    #[test]
+    fn test_encode_calldata() {
+        let signature = "transfer(address,uint256)";
+        let encoded = run_json(&["encode", signature, ADDRESS, "1000"]);
+        let calldata = encoded.as_str().unwrap();
+        assert_eq!(
+            calldata,
+            format!(
+                "0xa9059cbb{:0>64}{:064x}",
+                ADDRESS[2..].to_lowercase(),
+                1000
+            )
+        );
+
+        assert_eq!(
+            run_json(&["calldata", signature, calldata]),
+            json!({
+                "function": signature,
+                "selector": "0xa9059cbb",
+                "args": [ADDRESS, "1000"],
+            })
+        );
+        assert!(run(&[
+            "calldata".to_string(),
+            "f()".to_string(),
+            calldata.to_string()
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let types = "(uint8,string)[],int16[2],bool";
+        let encoded = run_json(&[
+            "encode",
+            &format!("({})", types),
+            r#"[[1, "a"], ["2", "bc"]]"#,
+            "[-1, 2]",
+            "true",
+        ]);
+
+        assert_eq!(
+            run_json(&["decode", types, encoded.as_str().unwrap()]),
+            json!([[["1", "a"], ["2", "bc"]], ["-1", "2"], true])
+        );
+    }
+
+    #[test]
+    fn test_selector() {
+        assert_eq!(
+            run_json(&["selector", "balanceOf(address)"]),
+            json!({"function": "balanceOf(address)", "selector": "0x70a08231"})
+        );
+    }
+
+    #[test]
+    fn test_dump() {
+        let args: Vec<String> = ["dump", "uint8,bool", &format!("0x{:064x}{:064x}", 7, 1)]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let dump = run(&args).unwrap();
+        assert_eq!(dump.lines().count(), 2);
+        assert!(dump.ends_with("element 1: bool = true"));
+    }
+
+    #[test]
+    fn test_diff() {
+        let run_args = |args: &[&str]| run(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>());
+        let left = format!("0x{:064x}{:064x}", 7, 1);
+
+        assert_eq!(
+            run_args(&["diff", "uint8,bool", &left, &left]).unwrap(),
+            "no difference"
+        );
+        assert!(run_args(&[
+            "diff",
+            "uint8,bool",
+            &left,
+            &format!("{:064x}{:064x}", 7, 0)
+        ])
+        .unwrap()
+        .starts_with("[1] at 0x20 (left) and 0x20 (right): element 1: bool = true ("));
+    }
+
+    #[test]
+    fn test_errors() {
+        let run_args = |args: &[&str]| run(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>());
+
+        assert!(run_args(&[]).is_err());
+        assert!(run_args(&["selector", "f(uint7)"]).is_err());
+        assert!(run_args(&["encode", "f(uint8)", "256"]).is_err());
+        assert!(run_args(&["encode", "f(uint8)", "1", "2"]).is_err());
+        assert!(run_args(&["encode", "f(uint8[2])", "[1]"]).is_err());
+        assert!(run_args(&["decode", "uint8", "00"]).is_err());
+    }
+}
@@ -0,0 +1,277 @@
+use crate::{
+    address::Address,
+    de::{from_str, from_str_with_options, DeserializerOptions, RefReadSeek, SeqIter},
+    io::Cursor,
+    ser::{encoded_len, to_string, to_writer_streaming},
+};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Debug;
+use oasis_std::types::{H160, H256, U256};
+use proptest::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+fn round_trip<T>(value: &T) -> core::result::Result<(), TestCaseError>
+where
+    T: Serialize + for<'de> Deserialize<'de> + PartialEq + Debug,
+{
+    let encoded = to_string(value).map_err(|err| TestCaseError::fail(err.to_string()))?;
+
+    let mut streamed = Vec::new();
+    to_writer_streaming(&mut streamed, value)
+        .map_err(|err| TestCaseError::fail(err.to_string()))?;
+    prop_assert_eq!(&streamed[..], encoded.as_bytes(), "streaming encoding");
+    prop_assert_eq!(encoded_len(value).ok(), Some(encoded.len() / 2));
+
+    let decoded: T = from_str(&encoded).map_err(|err| TestCaseError::fail(err.to_string()))?;
+    prop_assert_eq!(&decoded, value, "lenient decoding of {}", encoded);
+
+    let decoded: T = from_str_with_options(&encoded, DeserializerOptions::new().strict(true))
+        .map_err(|err| TestCaseError::fail(err.to_string()))?;
+    prop_assert_eq!(&decoded, value, "strict decoding of {}", encoded);
+
+    Ok(())
+}
+
+/// seq_iter_matches checks that iterating over the elements of an array
+/// gives the same elements as decoding the whole array
+fn seq_iter_matches<T>(values: &[T]) -> core::result::Result<(), TestCaseError>
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let encoded = to_string(values).map_err(|err| TestCaseError::fail(err.to_string()))?;
+
+    for options in [
+        DeserializerOptions::new(),
+        DeserializerOptions::new().strict(true),
+    ] {
+        let mut read = RefReadSeek::new(Cursor::new(&encoded));
+        let decoded = SeqIter::<_, T>::with_options(&mut read, options)
+            .and_then(|iter| iter.collect::<crate::Result<Vec<T>>>())
+            .map_err(|err| TestCaseError::fail(err.to_string()))?;
+        prop_assert_eq!(&decoded[..], values, "iterating over {}", encoded);
+    }
+
+    Ok(())
+}
+
+macro_rules! boundary {
+    ($ty:ty) => {
+        prop_oneof![
+            Just(<$ty>::min_value()),
+            Just(<$ty>::max_value()),
+            Just(0 as $ty),
+            Just(1 as $ty),
+            Just(32 as $ty),
+            any::<$ty>(),
+        ]
+    };
+}
+
+fn word() -> impl Strategy<Value = [u8; 32]> {
+    prop_oneof![
+        Just([0u8; 32]),
+        Just([0xffu8; 32]),
+        any::<[u8; 32]>(),
+        any::<u64>().prop_map(|n| {
+            let mut v = [0u8; 32];
+            v[24..].copy_from_slice(&n.to_be_bytes());
+            v
+        }),
+    ]
+}
+
+fn h256() -> impl Strategy<Value = H256> {
+    word().prop_map(H256::from)
+}
+
+fn u256() -> impl Strategy<Value = U256> {
+    word().prop_map(U256::from)
+}
+
+fn h160() -> impl Strategy<Value = H160> {
+    any::<[u8; 20]>().prop_map(H160::from)
+}
+
+fn bytes() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        Just(Vec::new()),
+        prop::collection::vec(any::<u8>(), 0..40),
+        prop::collection::vec(any::<u8>(), 200..400),
+    ]
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+struct Static {
+    value: u64,
+    hash: H256,
+    flags: [bool; 2],
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+struct Dynamic {
+    name: String,
+    value: u32,
+    data: Vec<u8>,
+    inner: Static,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+struct Composed {
+    items: Vec<Dynamic>,
+    pairs: Vec<(u64, String)>,
+    fixed: Vec<(H256, [u32; 4])>,
+    nested: Vec<Vec<(String, (H256, String))>>,
+    maybe: Option<Static>,
+    amount: U256,
+}
+
+fn small_string() -> impl Strategy<Value = String> {
+    prop_oneof![Just(String::new()), any::<String>(), "\\PC{30,80}"]
+}
+
+fn static_struct() -> impl Strategy<Value = Static> {
+    (boundary!(u64), h256(), any::<[bool; 2]>()).prop_map(|(value, hash, flags)| Static {
+        value,
+        hash,
+        flags,
+    })
+}
+
+fn dynamic_struct() -> impl Strategy<Value = Dynamic> {
+    (small_string(), boundary!(u32), bytes(), static_struct()).prop_map(
+        |(name, value, data, inner)| Dynamic {
+            name,
+            value,
+            data,
+            inner,
+        },
+    )
+}
+
+fn composed() -> impl Strategy<Value = Composed> {
+    (
+        prop::collection::vec(dynamic_struct(), 0..3),
+        prop::collection::vec((boundary!(u64), small_string()), 0..3),
+        prop::collection::vec((h256(), any::<[u32; 4]>()), 0..3),
+        prop::collection::vec(
+            prop::collection::vec((small_string(), (h256(), small_string())), 0..3),
+            0..3,
+        ),
+        prop::option::of(static_struct()),
+        u256(),
+    )
+        .prop_map(|(items, pairs, fixed, nested, maybe, amount)| Composed {
+            items,
+            pairs,
+            fixed,
+            nested,
+            maybe,
+            amount,
+        })
+}
+
+proptest! {
+    #[test]
+    fn prop_bool(value in any::<bool>()) {
+        round_trip(&value)?;
+    }
+
+    #[test]
+    fn prop_ints(
+        a in boundary!(u8),
+        b in boundary!(u16),
+        c in boundary!(u32),
+        d in boundary!(u64),
+        e in boundary!(i8),
+        f in boundary!(i16),
+        g in boundary!(i32),
+        h in boundary!(i64),
+    ) {
+        round_trip(&a)?;
+        round_trip(&b)?;
+        round_trip(&c)?;
+        round_trip(&d)?;
+        round_trip(&e)?;
+        round_trip(&f)?;
+        round_trip(&g)?;
+        round_trip(&h)?;
+    }
+
+    #[test]
+    fn prop_fixed(hash in h256(), amount in u256(), addr in h160(), bytes in any::<[u8; 20]>()) {
+        round_trip(&hash)?;
+        round_trip(&amount)?;
+        round_trip(&addr)?;
+        round_trip(&Address::from(bytes))?;
+    }
+
+    #[test]
+    fn prop_string(value in small_string()) {
+        round_trip(&value)?;
+    }
+
+    #[test]
+    fn prop_bytes(value in bytes()) {
+        round_trip(&value)?;
+    }
+
+    #[test]
+    fn prop_option(value in prop::option::of(small_string()), n in prop::option::of(boundary!(u64))) {
+        round_trip(&value)?;
+        round_trip(&n)?;
+    }
+
+    #[test]
+    fn prop_nested_seq(
+        value in prop::collection::vec(prop::collection::vec(boundary!(u64), 0..4), 0..4),
+        strings in prop::collection::vec(prop::collection::vec(small_string(), 0..3), 0..3),
+    ) {
+        round_trip(&value)?;
+        round_trip(&strings)?;
+    }
+
+    #[test]
+    fn prop_static_tuple(value in (boundary!(u64), boundary!(u32), any::<bool>()), array in any::<[u8; 3]>()) {
+        round_trip(&value)?;
+        round_trip(&array)?;
+    }
+
+    #[test]
+    fn prop_seq_of_static_tuples(
+        value in prop::collection::vec((boundary!(u64), boundary!(u8)), 0..4),
+        arrays in prop::collection::vec(any::<[u8; 3]>(), 0..4),
+    ) {
+        round_trip(&value)?;
+        round_trip(&arrays)?;
+    }
+
+    #[test]
+    fn prop_mixed_tuple(value in (boundary!(u64), small_string(), h256(), prop::collection::vec(boundary!(u32), 0..3))) {
+        round_trip(&value)?;
+    }
+
+    #[test]
+    fn prop_structs(value in static_struct(), dynamic in dynamic_struct()) {
+        round_trip(&value)?;
+        round_trip(&dynamic)?;
+    }
+
+    #[test]
+    fn prop_composed(value in composed()) {
+        round_trip(&value)?;
+    }
+
+    #[test]
+    fn prop_seq_iter(
+        pairs in prop::collection::vec((boundary!(u64), boundary!(u8)), 0..4),
+        items in prop::collection::vec(dynamic_struct(), 0..4),
+        nested in prop::collection::vec(prop::collection::vec((small_string(), (h256(), small_string())), 0..3), 0..3),
+    ) {
+        seq_iter_matches(&pairs)?;
+        seq_iter_matches(&items)?;
+        seq_iter_matches(&nested)?;
+    }
+}
@@ -1,26 +1,47 @@
-use std::io;
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
 
 use serde::ser::{self, SerializeSeq};
 
 use super::{
     custom_ser,
-    error::{Error, Result},
-    eth,
+    error::{Error, ErrorKind, Result},
+    eth, io,
 };
 
+/// Serializer writes the encoding of a value to a writer.
+///
+/// `to_writer` and friends encode the whole value with two passes over
+/// it, which writes every word once. Values serialized directly through
+/// the `serde::Serializer` implementation of `&mut Serializer` have the
+/// elements at the root encoded that way, and then put together.
 pub struct Serializer<W> {
     writer: W,
 
     // current_custom_type is used to set the current state of any type whose serialization
     // is implemented in the serializer.
     current_custom_serializer: Option<eth::Fixed>,
+
+    // params is set when a tuple at the root is written as the list of
+    // parameters of a function call, which has no offset before it.
+    params: bool,
 }
 
 impl<W: io::Write> Serializer<W> {
     pub fn new(writer: W) -> Self {
         Serializer {
-            writer: writer,
+            writer,
+            current_custom_serializer: None,
+            params: false,
+        }
+    }
+
+    /// with_params creates a serializer that writes a tuple at the
+    /// root as the list of parameters of a function call
+    pub fn with_params(writer: W) -> Self {
+        Serializer {
+            writer,
             current_custom_serializer: None,
+            params: true,
         }
     }
 
@@ -32,6 +53,24 @@ impl<W: io::Write> Serializer<W> {
     pub fn into_inner(self) -> W {
         self.writer
     }
+
+    /// write_value encodes a value that is not a compound and writes it
+    fn write_value<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
+        let encoded = encode(value, self.params)?;
+        self.write(&encoded)
+    }
+}
+
+/// Bytes serializes a byte slice with `serialize_bytes`
+struct Bytes<'a>(&'a [u8]);
+
+impl<'a> ser::Serialize for Bytes<'a> {
+    fn serialize<S: ser::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
 }
 
 impl<'a, W: io::Write> ser::Serializer for &'a mut Serializer<W> {
@@ -46,9 +85,12 @@ impl<'a, W: io::Write> ser::Serializer for &'a mut Serializer<W> {
     type SerializeStruct = RootCompound<'a, W>;
     type SerializeStructVariant = RootCompound<'a, W>;
 
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
     fn serialize_bool(self, value: bool) -> Result<Self::Ok> {
-        let encoded = eth::encode_bool(value);
-        self.write(&encoded.into_bytes())
+        self.write_value(&value)
     }
 
     fn serialize_i8(self, value: i8) -> Result<Self::Ok> {
@@ -64,8 +106,7 @@ impl<'a, W: io::Write> ser::Serializer for &'a mut Serializer<W> {
     }
 
     fn serialize_i64(self, value: i64) -> Result<Self::Ok> {
-        let encoded = eth::encode_i64(value);
-        self.write(&encoded.into_bytes())
+        self.write_value(&value)
     }
 
     fn serialize_u8(self, value: u8) -> Result<Self::Ok> {
@@ -81,16 +122,15 @@ impl<'a, W: io::Write> ser::Serializer for &'a mut Serializer<W> {
     }
 
     fn serialize_u64(self, value: u64) -> Result<Self::Ok> {
-        let encoded = eth::encode_u64(value);
-        self.write(&encoded.into_bytes())
+        self.write_value(&value)
     }
 
     fn serialize_f32(self, _value: f32) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("f32"))
     }
 
     fn serialize_f64(self, _value: f64) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("f64"))
     }
 
     fn serialize_char(self, value: char) -> Result<Self::Ok> {
@@ -99,14 +139,11 @@ impl<'a, W: io::Write> ser::Serializer for &'a mut Serializer<W> {
     }
 
     fn serialize_str(self, value: &str) -> Result<Self::Ok> {
-        self.serialize_bytes(&value.to_string().into_bytes())
+        self.serialize_bytes(value.as_bytes())
     }
 
     fn serialize_bytes(self, value: &[u8]) -> Result<Self::Ok> {
-        let encoded = eth::encode_bytes(value);
-        self.writer
-            .write_all(&encoded.into_bytes())
-            .map_err(Error::io)
+        self.write_value(&Bytes(value))
     }
 
     fn serialize_none(self) -> Result<Self::Ok> {
@@ -158,44 +195,23 @@ impl<'a, W: io::Write> ser::Serializer for &'a mut Serializer<W> {
     }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
-        let root = match len {
-            Some(l) => Node::Seq(Vec::with_capacity(l)),
-            None => Node::Seq(Vec::new()),
-        };
-
-        Ok(RootCompound::Standard {
+        Ok(RootCompound {
             writer: self,
-            ser: NodeSerializer::new(root),
+            seq: true,
+            fixed: None,
+            elements: Vec::with_capacity(len.unwrap_or(0)),
         })
     }
 
     fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
-        let custom_serializer = self.current_custom_serializer;
-        self.current_custom_serializer = None;
+        let fixed = self.current_custom_serializer.take().map(fixed_serializer);
 
-        match custom_serializer {
-            Some(t) => match t {
-                eth::Fixed::H256 => Ok(RootCompound::BigInteger {
-                    writer: self,
-                    ser: custom_ser::BasicEthSerializer::new_hash(32),
-                }),
-                eth::Fixed::H160 => Ok(RootCompound::BigInteger {
-                    writer: self,
-                    ser: custom_ser::BasicEthSerializer::new_hash(20),
-                }),
-                eth::Fixed::U256 => Ok(RootCompound::BigInteger {
-                    writer: self,
-                    ser: custom_ser::BasicEthSerializer::new_uint(32),
-                }),
-            },
-            None => {
-                let root = Node::Tuple(Vec::with_capacity(len));
-                Ok(RootCompound::Standard {
-                    writer: self,
-                    ser: NodeSerializer::new(root),
-                })
-            }
-        }
+        Ok(RootCompound {
+            writer: self,
+            seq: false,
+            fixed,
+            elements: Vec::with_capacity(len),
+        })
     }
 
     fn serialize_tuple_struct(
@@ -217,7 +233,7 @@ impl<'a, W: io::Write> ser::Serializer for &'a mut Serializer<W> {
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("map"))
     }
 
     fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
@@ -235,326 +251,326 @@ impl<'a, W: io::Write> ser::Serializer for &'a mut Serializer<W> {
     }
 }
 
-enum Node {
-    // Fixed sized types do not have headers we only need to
-    // keep track of its value
-    Fixed(String),
+/// compound_impls implements the serde compound traits for a type that
+/// implements `SerializeSeq`, forwarding every element to it
+macro_rules! compound_impls {
+    ($ty:ty, $($generics:tt)*) => {
+        impl<$($generics)*> ser::SerializeTuple for $ty {
+            type Ok = ();
+            type Error = Error;
 
-    // Dynamic sized types have an offset, size and content
-    Dynamic(String),
+            fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
+                ser::SerializeSeq::serialize_element(self, value)
+            }
 
-    // A compound type will not have specific content
-    // it will be generated by its children. It adds an
-    // structure to the sequence by writing the offset and
-    // the size of the vector when serialized
-    Seq(Vec<Node>),
+            fn end(self) -> Result<()> {
+                ser::SerializeSeq::end(self)
+            }
+        }
 
-    // A compound type will not have specific content
-    // it will be generated by its children. It does not
-    // write structe information when serialized
-    Tuple(Vec<Node>),
-}
+        impl<$($generics)*> ser::SerializeTupleStruct for $ty {
+            type Ok = ();
+            type Error = Error;
 
-enum SerializationMode {
-    Tuple,
-    Sequence,
-}
+            fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
+                ser::SerializeSeq::serialize_element(self, value)
+            }
 
-impl Node {
-    fn serialize_simple(head: &str, tail: &str) -> String {
-        let mut result = String::with_capacity(head.len() + tail.len());
-        result.push_str(head);
-        result.push_str(tail);
-        result
-    }
-
-    fn serialize_nodes(nodes: Vec<Node>, mode: SerializationMode) -> String {
-        let result = Node::serialize_compound_to_simple(nodes, mode);
-        result.serialize()
-    }
-
-    fn aggregate_simple_nodes_in_sequence(nodes: Vec<Node>) -> Node {
-        let mut head = String::from(eth::encode_u64(nodes.len() as u64));
-        let mut tail = String::new();
-        let mut offset = Node::calculate_header_len_from_simple_nodes(&nodes);
-
-        for node in nodes {
-            match node {
-                Node::Fixed(h) => head.push_str(&h),
-                Node::Dynamic(t) => {
-                    head.push_str(&eth::encode_u64(offset as u64));
-                    tail.push_str(&t);
-                    offset += t.len() >> 1;
-                }
-                Node::Seq(_) | Node::Tuple(_) => unreachable!(),
+            fn end(self) -> Result<()> {
+                ser::SerializeSeq::end(self)
             }
         }
 
-        let serialized = Node::serialize_simple(&head, &tail);
-        Node::Dynamic(serialized)
-    }
+        impl<$($generics)*> ser::SerializeTupleVariant for $ty {
+            type Ok = ();
+            type Error = Error;
 
-    fn should_tuple_have_head(nodes: &Vec<Node>) -> bool {
-        nodes.iter().any(|t| match t {
-            Node::Fixed(_) => false,
-            Node::Dynamic(_) => true,
-            Node::Seq(_) | Node::Tuple(_) => unreachable!(),
-        })
-    }
+            fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
+                ser::SerializeSeq::serialize_element(self, value)
+            }
 
-    fn aggregate_simple_nodes_in_tuple(nodes: Vec<Node>) -> Node {
-        let needs_head = Node::should_tuple_have_head(&nodes);
-        let mut head = String::new();
-        let mut tail = String::new();
-        let mut offset = Node::calculate_header_len_from_simple_nodes(&nodes);
-
-        for node in nodes {
-            match node {
-                Node::Fixed(h) => {
-                    head.push_str(&h);
-                }
-                Node::Dynamic(t) => {
-                    head.push_str(&eth::encode_u64(offset as u64));
-                    tail.push_str(&t);
-                    offset += t.len() >> 1;
-                }
-                Node::Seq(_) | Node::Tuple(_) => unreachable!(),
+            fn end(self) -> Result<()> {
+                ser::SerializeSeq::end(self)
             }
         }
 
-        let serialized = Node::serialize_simple(&head, &tail);
-        if needs_head {
-            Node::Dynamic(serialized)
-        } else {
-            Node::Fixed(serialized)
-        }
-    }
+        impl<$($generics)*> ser::SerializeMap for $ty {
+            type Ok = ();
+            type Error = Error;
 
-    fn calculate_header_len_from_simple_nodes(nodes: &Vec<Node>) -> usize {
-        nodes.iter().fold(0, |acc, node| match node {
-            Node::Fixed(h) => acc + (h.len() >> 1),
-            Node::Dynamic(_) => acc + 32,
-            Node::Seq(_) | Node::Tuple(_) => unreachable!(),
-        })
-    }
+            fn serialize_key<T: ?Sized + ser::Serialize>(&mut self, _key: &T) -> Result<()> {
+                Err(Error::not_implemented("map"))
+            }
 
-    fn serialize_compound_to_simple(nodes: Vec<Node>, mode: SerializationMode) -> Node {
-        let mut simple_nodes = Vec::with_capacity(nodes.len());
+            fn serialize_value<T: ?Sized + ser::Serialize>(&mut self, _value: &T) -> Result<()> {
+                Err(Error::not_implemented("map"))
+            }
 
-        for node in nodes {
-            let simple_node = Node::serialize_node_to_simple(node);
-            simple_nodes.push(simple_node);
+            fn end(self) -> Result<()> {
+                Err(Error::not_implemented("map"))
+            }
         }
 
-        match mode {
-            SerializationMode::Sequence => Node::aggregate_simple_nodes_in_sequence(simple_nodes),
-            SerializationMode::Tuple => Node::aggregate_simple_nodes_in_tuple(simple_nodes),
-        }
-    }
+        impl<$($generics)*> ser::SerializeStruct for $ty {
+            type Ok = ();
+            type Error = Error;
 
-    fn serialize_dynamic(content: String) -> String {
-        let offset = 32;
-        let mut new_content = eth::encode_u64(offset as u64);
-        new_content.push_str(&content);
-        new_content
-    }
+            fn serialize_field<T: ?Sized + ser::Serialize>(
+                &mut self,
+                _key: &'static str,
+                value: &T,
+            ) -> Result<()> {
+                ser::SerializeSeq::serialize_element(self, value)
+            }
 
-    fn serialize_node_to_simple(node: Node) -> Node {
-        match node {
-            Node::Seq(vec) => Node::serialize_compound_to_simple(vec, SerializationMode::Sequence),
-            Node::Tuple(vec) => Node::serialize_compound_to_simple(vec, SerializationMode::Tuple),
-            node => node,
+            fn end(self) -> Result<()> {
+                ser::SerializeSeq::end(self)
+            }
         }
-    }
 
-    fn serialize(self) -> String {
-        match self {
-            Node::Fixed(head) => Node::serialize_simple(&head, ""),
-            Node::Dynamic(content) => Node::serialize_dynamic(content),
-            Node::Seq(vec) => Node::serialize_nodes(vec, SerializationMode::Sequence),
-            Node::Tuple(vec) => Node::serialize_nodes(vec, SerializationMode::Tuple),
-        }
-    }
+        impl<$($generics)*> ser::SerializeStructVariant for $ty {
+            type Ok = ();
+            type Error = Error;
+
+            fn serialize_field<T: ?Sized + ser::Serialize>(
+                &mut self,
+                _key: &'static str,
+                value: &T,
+            ) -> Result<()> {
+                ser::SerializeSeq::serialize_element(self, value)
+            }
 
-    fn push(&mut self, node: Node) {
-        match self {
-            Node::Fixed(_) => panic!("attempt to push node to Node::Fixed"),
-            Node::Dynamic(_) => panic!("attempt to push node to Node::Dynamic"),
-            Node::Seq(children) => children.push(node),
-            Node::Tuple(children) => children.push(node),
+            fn end(self) -> Result<()> {
+                ser::SerializeSeq::end(self)
+            }
         }
-    }
+    };
 }
 
-pub enum NodeCompound<'a> {
-    Standard {
-        base: &'a mut NodeSerializer,
-        ser: NodeSerializer,
-    },
-    BigInteger {
-        base: &'a mut NodeSerializer,
-        ser: custom_ser::BasicEthSerializer,
-    },
+/// RootCompound encodes every element of a compound at the root on its
+/// own, and writes them with their offsets once the compound ends
+pub struct RootCompound<'a, W: 'a> {
+    writer: &'a mut Serializer<W>,
+    seq: bool,
+    fixed: Option<custom_ser::BasicEthSerializer>,
+    elements: Vec<(Vec<u8>, Size)>,
 }
 
-impl<'a> ser::SerializeSeq for NodeCompound<'a> {
+impl<'a, W: io::Write> ser::SerializeSeq for RootCompound<'a, W> {
     type Ok = ();
     type Error = Error;
 
     fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
-        match self {
-            NodeCompound::Standard { base: _, ser } => value.serialize(ser),
-            NodeCompound::BigInteger { base: _, ser } => value.serialize(ser),
+        match &mut self.fixed {
+            Some(ser) => value.serialize(ser),
+            None => {
+                self.elements.push(encode_with(value, |_| false)?);
+                Ok(())
+            }
         }
     }
 
     fn end(self) -> Result<()> {
-        match self {
-            NodeCompound::Standard { base, ser } => {
-                let node = ser.into_inner();
-                base.push(node);
-            }
-            NodeCompound::BigInteger { base, ser } => {
-                base.push(Node::Fixed(ser.serialize()));
-            }
+        if let Some(ser) = self.fixed {
+            return self.writer.write(ser.serialize().as_bytes());
         }
 
-        Ok(())
+        let mut size = Size {
+            tuple: !self.seq,
+            ..Size::default()
+        };
+        for (_, element) in &self.elements {
+            size.add(element);
+        }
+        let size = size.finish();
+
+        let offset = needs_offset(&size, self.writer.params);
+        let mut ser = WordSerializer::new(&[], offset, buffer(offset, &size))?;
+        let mut compound = ser.compound(size)?;
+        for (encoded, element) in &self.elements {
+            let pos = compound.place(element)?;
+            slot(&mut compound.ser.out, pos, element.len)?.copy_from_slice(encoded);
+        }
+
+        self.writer.write(&ser.out)
     }
 }
 
-impl<'a> ser::SerializeTuple for NodeCompound<'a> {
-    type Ok = ();
-    type Error = Error;
-
-    fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
-        ser::SerializeSeq::serialize_element(self, value)
-    }
+compound_impls!(RootCompound<'a, W>, 'a, W: io::Write);
 
-    fn end(self) -> Result<()> {
-        ser::SerializeSeq::end(self)
-    }
-}
+/// Size is what the first pass records for every value, in the order in
+/// which the values are serialized
+#[derive(Clone, Copy, Default)]
+struct Size {
+    // len is the length in bytes of the encoding, without the offset
+    // before it when the value is dynamic
+    len: usize,
 
-impl<'a> ser::SerializeTupleStruct for NodeCompound<'a> {
-    type Ok = ();
-    type Error = Error;
+    // head is the length of the heads of the elements of a compound
+    head: usize,
 
-    fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
-        ser::SerializeSeq::serialize_element(self, value)
-    }
+    // count is the number of elements of a compound
+    count: usize,
 
-    fn end(self) -> Result<()> {
-        ser::SerializeSeq::end(self)
-    }
+    dynamic: bool,
+    tuple: bool,
 }
 
-impl<'a> ser::SerializeTupleVariant for NodeCompound<'a> {
-    type Ok = ();
-    type Error = Error;
-
-    fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
-        ser::SerializeSeq::serialize_element(self, value)
+impl Size {
+    fn word() -> Size {
+        Size {
+            len: 32,
+            ..Size::default()
+        }
     }
 
-    fn end(self) -> Result<()> {
-        ser::SerializeSeq::end(self)
+    fn bytes(len: usize) -> Size {
+        Size {
+            len: 32 + eth::padded_len(len),
+            dynamic: true,
+            ..Size::default()
+        }
     }
-}
-
-impl<'a> ser::SerializeMap for NodeCompound<'a> {
-    type Ok = ();
-    type Error = Error;
 
-    fn serialize_key<T: ?Sized + ser::Serialize>(&mut self, _key: &T) -> Result<()> {
-        Err(Error::not_implemented())
+    /// add adds an element to a compound. A dynamic element is in the
+    /// head as an offset to its content in the tail
+    fn add(&mut self, element: &Size) {
+        self.count += 1;
+        if element.dynamic {
+            self.head += 32;
+            self.len += element.len;
+            self.dynamic = true;
+        } else {
+            self.head += element.len;
+        }
     }
 
-    fn serialize_value<T: ?Sized + ser::Serialize>(&mut self, _value: &T) -> Result<()> {
-        Err(Error::not_implemented())
+    /// finish computes the length of a compound once all of its
+    /// elements are added. A sequence is prefixed by the number of
+    /// elements and is always dynamic
+    fn finish(mut self) -> Size {
+        self.len += self.head;
+        if !self.tuple {
+            self.len += 32;
+            self.dynamic = true;
+        }
+        self
     }
+}
 
-    fn end(self) -> Result<()> {
-        Err(Error::not_implemented())
+fn fixed_serializer(fixed: eth::Fixed) -> custom_ser::BasicEthSerializer {
+    match fixed {
+        eth::Fixed::H256 => custom_ser::BasicEthSerializer::new_hash(32),
+        eth::Fixed::H160 => custom_ser::BasicEthSerializer::new_hash(20),
+        eth::Fixed::U256 => custom_ser::BasicEthSerializer::new_uint(32),
     }
 }
 
-impl<'a> ser::SerializeStruct for NodeCompound<'a> {
-    type Ok = ();
-    type Error = Error;
-
-    fn serialize_field<T: ?Sized + ser::Serialize>(
-        &mut self,
-        _key: &'static str,
-        value: &T,
-    ) -> Result<()> {
-        ser::SerializeSeq::serialize_element(self, value)
-    }
+fn unstable_error() -> Error {
+    Error::new(
+        ErrorKind::Custom,
+        "value serialized differently in each pass of the serializer",
+    )
+}
 
-    fn end(self) -> Result<()> {
-        ser::SerializeSeq::end(self)
+/// needs_offset returns the length of the offset that precedes a value
+/// at the root, which is only there when the value is dynamic and not
+/// the list of parameters of a function call
+fn needs_offset(size: &Size, params: bool) -> usize {
+    if size.dynamic && !(params && size.tuple) {
+        32
+    } else {
+        0
     }
 }
 
-impl<'a> ser::SerializeStructVariant for NodeCompound<'a> {
-    type Ok = ();
-    type Error = Error;
+/// SizeSerializer is the first pass of the serializer. It computes the
+/// size of every value without encoding any of them
+#[derive(Default)]
+struct SizeSerializer {
+    sizes: Vec<Size>,
 
-    fn serialize_field<T: ?Sized + ser::Serialize>(
-        &mut self,
-        _key: &'static str,
-        value: &T,
-    ) -> Result<()> {
-        ser::SerializeSeq::serialize_element(self, value)
+    // current_custom_type is used to set the current state of any type whose serialization
+    // is implemented in the serializer.
+    current_custom_serializer: Option<eth::Fixed>,
+}
+
+impl SizeSerializer {
+    fn push(&mut self, size: Size) -> Result<()> {
+        self.sizes.push(size);
+        Ok(())
     }
 
-    fn end(self) -> Result<()> {
-        ser::SerializeSeq::end(self)
+    fn compound(&mut self, tuple: bool) -> SizeCompound<'_> {
+        let index = self.sizes.len();
+        self.sizes.push(Size::default());
+        SizeCompound {
+            size: Size {
+                tuple,
+                ..Size::default()
+            },
+            index,
+            fixed: false,
+            ser: self,
+        }
     }
 }
 
-pub struct NodeSerializer {
-    root: Node,
+struct SizeCompound<'a> {
+    ser: &'a mut SizeSerializer,
+    size: Size,
+    index: usize,
 
-    // current_custom_type is used to set the current state of any type whose serialization
-    // is implemented in the serializer.
-    current_custom_serializer: Option<eth::Fixed>,
+    // fixed is set for the types handled by `BasicEthSerializer`, which
+    // are always a single word
+    fixed: bool,
 }
 
-impl NodeSerializer {
-    fn new(node: Node) -> Self {
-        NodeSerializer {
-            root: node,
-            current_custom_serializer: None,
+impl<'a> ser::SerializeSeq for SizeCompound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
+        if self.fixed {
+            return Ok(());
         }
-    }
 
-    fn into_inner(self) -> Node {
-        self.root
+        self.ser.current_custom_serializer = None;
+        let index = self.ser.sizes.len();
+        value.serialize(&mut *self.ser)?;
+        let element = self.ser.sizes.get(index).ok_or_else(unstable_error)?;
+        self.size.add(element);
+        Ok(())
     }
 
-    fn push(&mut self, node: Node) {
-        self.current_custom_serializer = None;
-        self.root.push(node);
+    fn end(self) -> Result<()> {
+        if !self.fixed {
+            self.ser.sizes[self.index] = self.size.finish();
+        }
+        Ok(())
     }
 }
 
-impl<'a> ser::Serializer for &'a mut NodeSerializer {
+compound_impls!(SizeCompound<'a>, 'a);
+
+impl<'a> ser::Serializer for &'a mut SizeSerializer {
     type Ok = ();
     type Error = Error;
 
-    type SerializeSeq = NodeCompound<'a>;
-    type SerializeTuple = NodeCompound<'a>;
-    type SerializeTupleStruct = NodeCompound<'a>;
-    type SerializeTupleVariant = NodeCompound<'a>;
-    type SerializeMap = NodeCompound<'a>;
-    type SerializeStruct = NodeCompound<'a>;
-    type SerializeStructVariant = NodeCompound<'a>;
+    type SerializeSeq = SizeCompound<'a>;
+    type SerializeTuple = SizeCompound<'a>;
+    type SerializeTupleStruct = SizeCompound<'a>;
+    type SerializeTupleVariant = SizeCompound<'a>;
+    type SerializeMap = SizeCompound<'a>;
+    type SerializeStruct = SizeCompound<'a>;
+    type SerializeStructVariant = SizeCompound<'a>;
 
-    fn serialize_bool(self, value: bool) -> Result<Self::Ok> {
-        let encoded = eth::encode_bool(value);
-        self.root.push(Node::Fixed(encoded));
-        Ok(())
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, _value: bool) -> Result<Self::Ok> {
+        self.push(Size::word())
     }
 
     fn serialize_i8(self, value: i8) -> Result<Self::Ok> {
@@ -569,10 +585,8 @@ impl<'a> ser::Serializer for &'a mut NodeSerializer {
         self.serialize_i64(value as i64)
     }
 
-    fn serialize_i64(self, value: i64) -> Result<Self::Ok> {
-        let encoded = eth::encode_i64(value);
-        self.root.push(Node::Fixed(encoded));
-        Ok(())
+    fn serialize_i64(self, _value: i64) -> Result<Self::Ok> {
+        self.push(Size::word())
     }
 
     fn serialize_u8(self, value: u8) -> Result<Self::Ok> {
@@ -587,37 +601,28 @@ impl<'a> ser::Serializer for &'a mut NodeSerializer {
         self.serialize_u64(value as u64)
     }
 
-    fn serialize_u64(self, value: u64) -> Result<Self::Ok> {
-        let encoded = eth::encode_u64(value);
-        self.root.push(Node::Fixed(encoded));
-        Ok(())
+    fn serialize_u64(self, _value: u64) -> Result<Self::Ok> {
+        self.push(Size::word())
     }
 
     fn serialize_f32(self, _value: f32) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("f32"))
     }
 
     fn serialize_f64(self, _value: f64) -> Result<Self::Ok> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("f64"))
     }
 
     fn serialize_char(self, value: char) -> Result<Self::Ok> {
-        let mut buf = [0; 4];
-        self.serialize_str(value.encode_utf8(&mut buf))
+        self.push(Size::bytes(value.len_utf8()))
     }
 
     fn serialize_str(self, value: &str) -> Result<Self::Ok> {
-        self.serialize_bytes(&value.to_string().into_bytes())
+        self.push(Size::bytes(value.len()))
     }
 
     fn serialize_bytes(self, value: &[u8]) -> Result<Self::Ok> {
-        let encoded = eth::encode_bytes_dynamic(value);
-        let mut content = String::with_capacity(encoded.size().len() + encoded.content().len());
-        content.push_str(encoded.size());
-        content.push_str(encoded.content());
-        let node = Node::Dynamic(content);
-        self.root.push(node);
-        Ok(())
+        self.push(Size::bytes(value.len()))
     }
 
     fn serialize_none(self) -> Result<Self::Ok> {
@@ -632,7 +637,7 @@ impl<'a> ser::Serializer for &'a mut NodeSerializer {
     }
 
     fn serialize_unit(self) -> Result<Self::Ok> {
-        Ok(())
+        self.push(Size::default())
     }
 
     fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok> {
@@ -668,42 +673,22 @@ impl<'a> ser::Serializer for &'a mut NodeSerializer {
         value.serialize(self)
     }
 
-    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
-        let root = match len {
-            Some(l) => Node::Seq(Vec::with_capacity(l)),
-            None => Node::Seq(Vec::new()),
-        };
-
-        Ok(NodeCompound::Standard {
-            base: self,
-            ser: NodeSerializer::new(root),
-        })
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Ok(self.compound(false))
     }
 
-    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
-        match &self.current_custom_serializer {
-            Some(t) => match t {
-                eth::Fixed::H256 => Ok(NodeCompound::BigInteger {
-                    base: self,
-                    ser: custom_ser::BasicEthSerializer::new_hash(32),
-                }),
-                eth::Fixed::H160 => Ok(NodeCompound::BigInteger {
-                    base: self,
-                    ser: custom_ser::BasicEthSerializer::new_hash(20),
-                }),
-                eth::Fixed::U256 => Ok(NodeCompound::BigInteger {
-                    base: self,
-                    ser: custom_ser::BasicEthSerializer::new_uint(32),
-                }),
-            },
-            None => {
-                let root = Node::Tuple(Vec::with_capacity(len));
-                Ok(NodeCompound::Standard {
-                    base: self,
-                    ser: NodeSerializer::new(root),
-                })
-            }
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        if self.current_custom_serializer.take().is_some() {
+            self.sizes.push(Size::word());
+            return Ok(SizeCompound {
+                size: Size::word(),
+                index: self.sizes.len() - 1,
+                fixed: true,
+                ser: self,
+            });
         }
+
+        Ok(self.compound(true))
     }
 
     fn serialize_tuple_struct(
@@ -711,11 +696,7 @@ impl<'a> ser::Serializer for &'a mut NodeSerializer {
         _name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleStruct> {
-        let root = Node::Tuple(Vec::with_capacity(len));
-        Ok(NodeCompound::Standard {
-            base: self,
-            ser: NodeSerializer::new(root),
-        })
+        self.serialize_tuple(len)
     }
 
     fn serialize_tuple_variant(
@@ -725,23 +706,15 @@ impl<'a> ser::Serializer for &'a mut NodeSerializer {
         _variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        let root = Node::Tuple(Vec::with_capacity(len));
-        Ok(NodeCompound::Standard {
-            base: self,
-            ser: NodeSerializer::new(root),
-        })
+        self.serialize_tuple(len)
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
-        Err(Error::not_implemented())
+        Err(Error::not_implemented("map"))
     }
 
     fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
-        let root = Node::Tuple(Vec::with_capacity(len));
-        Ok(NodeCompound::Standard {
-            base: self,
-            ser: NodeSerializer::new(root),
-        })
+        self.serialize_tuple(len)
     }
 
     fn serialize_struct_variant(
@@ -751,148 +724,572 @@ impl<'a> ser::Serializer for &'a mut NodeSerializer {
         _variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStruct> {
-        let root = Node::Tuple(Vec::with_capacity(len));
-        Ok(NodeCompound::Standard {
-            base: self,
-            ser: NodeSerializer::new(root),
-        })
+        self.serialize_tuple(len)
     }
 }
 
-pub enum RootCompound<'a, W: 'a> {
-    Standard {
-        writer: &'a mut Serializer<W>,
-        ser: NodeSerializer,
-    },
-    BigInteger {
-        writer: &'a mut Serializer<W>,
-        ser: custom_ser::BasicEthSerializer,
-    },
+/// Output is where the second pass of the serializer puts the encoding.
+/// Positions are in bytes, and the hex encoding is twice as long
+trait Output {
+    /// word writes the word at `pos`
+    fn word<F: FnOnce(&mut [u8])>(&mut self, pos: usize, write: F) -> Result<()>;
+
+    /// bytes writes the length and the padded content of a byte
+    /// string at `pos`
+    fn bytes(&mut self, pos: usize, value: &[u8]) -> Result<()>;
 }
 
-impl<'a, W: io::Write> ser::SerializeSeq for RootCompound<'a, W> {
-    type Ok = ();
-    type Error = Error;
+/// buffer allocates the output for a value of `size`, with room for an
+/// offset to it at the start if `offset` is not zero
+fn buffer(offset: usize, size: &Size) -> Vec<u8> {
+    vec![b'0'; (offset + size.len) << 1]
+}
 
-    fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
-        match self {
-            RootCompound::Standard { writer: _, ser } => value.serialize(ser),
-            RootCompound::BigInteger { writer: _, ser } => value.serialize(ser),
+/// slot returns the hex encoding of `len` bytes at `pos`
+fn slot(out: &mut [u8], pos: usize, len: usize) -> Result<&mut [u8]> {
+    out.get_mut(pos << 1..(pos + len) << 1)
+        .ok_or_else(unstable_error)
+}
+
+/// A buffer is filled with zeros beforehand, so the padding of byte
+/// strings is already there
+impl Output for Vec<u8> {
+    fn word<F: FnOnce(&mut [u8])>(&mut self, pos: usize, write: F) -> Result<()> {
+        write(slot(self, pos, 32)?);
+        Ok(())
+    }
+
+    fn bytes(&mut self, pos: usize, value: &[u8]) -> Result<()> {
+        let content = slot(self, pos, Size::bytes(value.len()).len)?;
+        eth::write_u64(&mut content[..64], value.len() as u64);
+        eth::write_hex(&mut content[64..], value);
+        Ok(())
+    }
+}
+
+/// BUFFERED_BYTES is the length of the longest byte string that `Stream`
+/// buffers when it cannot be written yet. Longer ones are written by
+/// another pass over the value instead of being copied
+const BUFFERED_BYTES: usize = 512;
+
+/// Stream writes the encoding to a writer in order. What comes after
+/// something that is not written yet is buffered until it can be
+/// written, except for long byte strings, which the second pass writes.
+struct Stream<W> {
+    writer: W,
+
+    // written is the length in bytes of the encoding written so far
+    written: usize,
+
+    // pending holds the hex encoding of the parts that cannot be written
+    // yet, as contiguous runs by the position at which they start
+    pending: BTreeMap<usize, Vec<u8>>,
+
+    // deferred is set in the first pass when a long byte string cannot
+    // be written yet, and the second pass only writes those
+    deferred: bool,
+    second_pass: bool,
+}
+
+impl<W: io::Write> Stream<W> {
+    fn new(writer: W) -> Self {
+        Stream {
+            writer,
+            written: 0,
+            pending: BTreeMap::new(),
+            deferred: false,
+            second_pass: false,
         }
     }
 
-    fn end(self) -> Result<()> {
-        match self {
-            RootCompound::Standard { writer, ser } => {
-                let node = ser.into_inner();
-                let encoded = node.serialize();
-                writer.write(&encoded.into_bytes())
-            }
-            RootCompound::BigInteger { writer, ser } => writer.write(&ser.serialize().into_bytes()),
+    fn write(&mut self, hex: &[u8]) -> Result<()> {
+        self.writer.write_all(hex).map_err(Error::io)
+    }
+
+    /// flush writes the pending runs that follow what is written
+    fn flush(&mut self) -> Result<()> {
+        while let Some(run) = self.pending.remove(&self.written) {
+            self.write(&run)?;
+            self.written += run.len() >> 1;
         }
+        Ok(())
+    }
+
+    /// buffer keeps the hex encoding of the part at `pos` until it can be
+    /// written, appending it to the run that ends at `pos` if there is one
+    fn buffer(&mut self, pos: usize) -> &mut Vec<u8> {
+        let start = match self.pending.range(..pos).next_back() {
+            Some((start, run)) if start + (run.len() >> 1) == pos => *start,
+            _ => pos,
+        };
+        self.pending.entry(start).or_default()
     }
 }
 
-impl<'a, W: io::Write> ser::SerializeTuple for RootCompound<'a, W> {
-    type Ok = ();
-    type Error = Error;
+impl<W: io::Write> Output for Stream<W> {
+    fn word<F: FnOnce(&mut [u8])>(&mut self, pos: usize, write: F) -> Result<()> {
+        // the first pass writes or buffers every word
+        if self.second_pass {
+            return Ok(());
+        }
 
-    fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
-        ser::SerializeSeq::serialize_element(self, value)
+        let mut word = [b'0'; 64];
+        write(&mut word);
+        if pos != self.written {
+            self.buffer(pos).extend_from_slice(&word);
+            return Ok(());
+        }
+
+        self.write(&word)?;
+        self.written += 32;
+        self.flush()
     }
 
-    fn end(self) -> Result<()> {
-        ser::SerializeSeq::end(self)
+    fn bytes(&mut self, pos: usize, value: &[u8]) -> Result<()> {
+        if pos != self.written {
+            if self.second_pass || pos < self.written {
+                return Ok(());
+            }
+            if value.len() > BUFFERED_BYTES {
+                self.deferred = true;
+                return Ok(());
+            }
+
+            let content = Size::bytes(value.len()).len;
+            let run = self.buffer(pos);
+            let start = run.len();
+            run.resize(start + (content << 1), b'0');
+            eth::write_u64(&mut run[start..start + 64], value.len() as u64);
+            eth::write_hex(&mut run[start + 64..], value);
+            return Ok(());
+        }
+
+        let mut chunk = [b'0'; 1024];
+        eth::write_u64(&mut chunk[..64], value.len() as u64);
+        self.write(&chunk[..64])?;
+        for bytes in value.chunks(chunk.len() >> 1) {
+            eth::write_hex(&mut chunk, bytes);
+            self.write(&chunk[..bytes.len() << 1])?;
+        }
+
+        let padding = eth::padded_len(value.len()) - value.len();
+        self.write(&[b'0'; 64][..padding << 1])?;
+        self.written += Size::bytes(value.len()).len;
+        self.flush()
     }
 }
 
-impl<'a, W: io::Write> ser::SerializeTupleStruct for RootCompound<'a, W> {
-    type Ok = ();
-    type Error = Error;
+/// WordSerializer is the second pass of the serializer. With the sizes
+/// from the first pass it knows where every value goes, so it writes
+/// each word directly at its position in the output
+struct WordSerializer<'s, O> {
+    sizes: &'s [Size],
+
+    // next is the index in `sizes` of the next value to serialize
+    next: usize,
+
+    // pos is the position in bytes where the next value is written
+    pos: usize,
+
+    out: O,
+
+    // current_custom_type is used to set the current state of any type whose serialization
+    // is implemented in the serializer.
+    current_custom_serializer: Option<eth::Fixed>,
+}
+
+impl<'s, O: Output> WordSerializer<'s, O> {
+    /// new creates a serializer that writes a value to `out`, with an
+    /// offset to it at the start if `offset` is not zero
+    fn new(sizes: &'s [Size], offset: usize, mut out: O) -> Result<Self> {
+        if offset > 0 {
+            out.word(0, |word| eth::write_u64(word, offset as u64))?;
+        }
 
-    fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
-        ser::SerializeSeq::serialize_element(self, value)
+        Ok(WordSerializer {
+            sizes,
+            next: 0,
+            pos: offset,
+            out,
+            current_custom_serializer: None,
+        })
     }
 
-    fn end(self) -> Result<()> {
-        ser::SerializeSeq::end(self)
+    fn next_size(&mut self) -> Result<Size> {
+        let size = *self.sizes.get(self.next).ok_or_else(unstable_error)?;
+        self.next += 1;
+        Ok(size)
+    }
+
+    fn write_word<F: FnOnce(&mut [u8])>(&mut self, write: F) -> Result<()> {
+        self.next_size()?;
+        self.out.word(self.pos, write)
+    }
+
+    fn compound(&mut self, size: Size) -> Result<WordCompound<'_, 's, O>> {
+        let mut start = self.pos;
+        if !size.tuple {
+            let count = size.count as u64;
+            self.out.word(start, |word| eth::write_u64(word, count))?;
+            start += 32;
+        }
+
+        Ok(WordCompound {
+            start,
+            head: start,
+            tail: start + size.head,
+            fixed: None,
+            ser: self,
+        })
+    }
+}
+
+struct WordCompound<'a, 's, O> {
+    ser: &'a mut WordSerializer<'s, O>,
+
+    // start is the position of the first element, which offsets are
+    // relative to
+    start: usize,
+
+    // head and tail are the positions of the next head and tail
+    head: usize,
+    tail: usize,
+
+    fixed: Option<custom_ser::BasicEthSerializer>,
+}
+
+impl<'a, 's, O: Output> WordCompound<'a, 's, O> {
+    /// place returns the position of the next element. A dynamic element
+    /// goes in the tail, and its offset is written in the head
+    fn place(&mut self, element: &Size) -> Result<usize> {
+        if !element.dynamic {
+            self.head += element.len;
+            return Ok(self.head - element.len);
+        }
+
+        let offset = (self.tail - self.start) as u64;
+        self.ser
+            .out
+            .word(self.head, |word| eth::write_u64(word, offset))?;
+        self.head += 32;
+        self.tail += element.len;
+        Ok(self.tail - element.len)
     }
 }
 
-impl<'a, W: io::Write> ser::SerializeTupleVariant for RootCompound<'a, W> {
+impl<'a, 's, O: Output> ser::SerializeSeq for WordCompound<'a, 's, O> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
-        ser::SerializeSeq::serialize_element(self, value)
+    fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
+        if let Some(ser) = &mut self.fixed {
+            return value.serialize(ser);
+        }
+
+        self.ser.current_custom_serializer = None;
+        let element = *self
+            .ser
+            .sizes
+            .get(self.ser.next)
+            .ok_or_else(unstable_error)?;
+        self.ser.pos = self.place(&element)?;
+        value.serialize(&mut *self.ser)
     }
 
     fn end(self) -> Result<()> {
-        ser::SerializeSeq::end(self)
+        if let Some(ser) = self.fixed {
+            self.ser.out.word(self.start, |word| {
+                word.copy_from_slice(ser.serialize().as_bytes())
+            })?;
+        }
+        Ok(())
     }
 }
 
-impl<'a, W: io::Write> ser::SerializeMap for RootCompound<'a, W> {
+compound_impls!(WordCompound<'a, 's, O>, 'a, 's, O: Output);
+
+impl<'a, 's, O: Output> ser::Serializer for &'a mut WordSerializer<'s, O> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_key<T: ?Sized + ser::Serialize>(&mut self, _key: &T) -> Result<()> {
-        Err(Error::not_implemented())
+    type SerializeSeq = WordCompound<'a, 's, O>;
+    type SerializeTuple = WordCompound<'a, 's, O>;
+    type SerializeTupleStruct = WordCompound<'a, 's, O>;
+    type SerializeTupleVariant = WordCompound<'a, 's, O>;
+    type SerializeMap = WordCompound<'a, 's, O>;
+    type SerializeStruct = WordCompound<'a, 's, O>;
+    type SerializeStructVariant = WordCompound<'a, 's, O>;
+
+    fn is_human_readable(&self) -> bool {
+        false
     }
 
-    fn serialize_value<T: ?Sized + ser::Serialize>(&mut self, _value: &T) -> Result<()> {
-        Err(Error::not_implemented())
+    fn serialize_bool(self, value: bool) -> Result<Self::Ok> {
+        self.write_word(|word| eth::write_bool(word, value))
     }
 
-    fn end(self) -> Result<()> {
-        Err(Error::not_implemented())
+    fn serialize_i8(self, value: i8) -> Result<Self::Ok> {
+        self.serialize_i64(value as i64)
     }
-}
 
-impl<'a, W: io::Write> ser::SerializeStruct for RootCompound<'a, W> {
-    type Ok = ();
-    type Error = Error;
+    fn serialize_i16(self, value: i16) -> Result<Self::Ok> {
+        self.serialize_i64(value as i64)
+    }
 
-    fn serialize_field<T: ?Sized + ser::Serialize>(
-        &mut self,
-        _key: &'static str,
-        value: &T,
-    ) -> Result<()> {
-        ser::SerializeSeq::serialize_element(self, value)
+    fn serialize_i32(self, value: i32) -> Result<Self::Ok> {
+        self.serialize_i64(value as i64)
     }
 
-    fn end(self) -> Result<()> {
-        ser::SerializeSeq::end(self)
+    fn serialize_i64(self, value: i64) -> Result<Self::Ok> {
+        self.write_word(|word| eth::write_i64(word, value))
     }
-}
 
-impl<'a, W: io::Write> ser::SerializeStructVariant for RootCompound<'a, W> {
-    type Ok = ();
-    type Error = Error;
+    fn serialize_u8(self, value: u8) -> Result<Self::Ok> {
+        self.serialize_u64(value as u64)
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<Self::Ok> {
+        self.serialize_u64(value as u64)
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<Self::Ok> {
+        self.serialize_u64(value as u64)
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<Self::Ok> {
+        self.write_word(|word| eth::write_u64(word, value))
+    }
+
+    fn serialize_f32(self, _value: f32) -> Result<Self::Ok> {
+        Err(Error::not_implemented("f32"))
+    }
+
+    fn serialize_f64(self, _value: f64) -> Result<Self::Ok> {
+        Err(Error::not_implemented("f64"))
+    }
 
-    fn serialize_field<T: ?Sized + ser::Serialize>(
-        &mut self,
-        _key: &'static str,
+    fn serialize_char(self, value: char) -> Result<Self::Ok> {
+        let mut buf = [0; 4];
+        self.serialize_str(value.encode_utf8(&mut buf))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<Self::Ok> {
+        self.serialize_bytes(value.as_bytes())
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Self::Ok> {
+        let size = self.next_size()?;
+        if size.len != Size::bytes(value.len()).len {
+            return Err(unstable_error());
+        }
+        self.out.bytes(self.pos, value)
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok> {
+        let serializer = self.serialize_seq(Some(0))?;
+        serializer.end()
+    }
+
+    fn serialize_some<T: ?Sized + ser::Serialize>(self, value: &T) -> Result<Self::Ok> {
+        let mut serializer = self.serialize_seq(Some(1))?;
+        serializer.serialize_element(value)?;
+        serializer.end()
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok> {
+        self.next_size()?;
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<Self::Ok> {
+        self.serialize_unit()
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + ser::Serialize>(
+        self,
+        name: &'static str,
         value: &T,
-    ) -> Result<()> {
-        ser::SerializeSeq::serialize_element(self, value)
+    ) -> Result<Self::Ok> {
+        self.current_custom_serializer = eth::Fixed::get(name);
+        value.serialize(self)
     }
 
-    fn end(self) -> Result<()> {
-        ser::SerializeSeq::end(self)
+    fn serialize_newtype_variant<T: ?Sized + ser::Serialize>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok> {
+        self.current_custom_serializer = eth::Fixed::get(name);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        let size = self.next_size()?;
+        if size.tuple {
+            return Err(unstable_error());
+        }
+        self.compound(size)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        let size = self.next_size()?;
+        if let Some(fixed) = self.current_custom_serializer.take() {
+            return Ok(WordCompound {
+                start: self.pos,
+                head: self.pos,
+                tail: self.pos,
+                fixed: Some(fixed_serializer(fixed)),
+                ser: self,
+            });
+        }
+
+        if !size.tuple {
+            return Err(unstable_error());
+        }
+        self.compound(size)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(Error::not_implemented("map"))
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct> {
+        self.serialize_tuple(len)
     }
 }
 
-pub fn to_writer<W: io::Write, T: ?Sized + ser::Serialize>(writer: W, value: &T) -> Result<()> {
-    let mut ser = Serializer::new(writer);
+/// encode_with encodes a value with the two passes of the serializer.
+/// The value is preceded by an offset to it if `offset` returns true
+/// for its size
+fn encode_with<T, F>(value: &T, offset: F) -> Result<(Vec<u8>, Size)>
+where
+    T: ?Sized + ser::Serialize,
+    F: FnOnce(&Size) -> bool,
+{
+    let mut sizes = SizeSerializer::default();
+    value.serialize(&mut sizes)?;
+    let size = sizes.sizes.first().copied().unwrap_or_default();
+
+    let offset = if offset(&size) { 32 } else { 0 };
+    let mut ser = WordSerializer::new(&sizes.sizes, offset, buffer(offset, &size))?;
     value.serialize(&mut ser)?;
+    if ser.next != sizes.sizes.len() {
+        return Err(unstable_error());
+    }
+
+    Ok((ser.out, size))
+}
+
+fn encode<T: ?Sized + ser::Serialize>(value: &T, params: bool) -> Result<Vec<u8>> {
+    let (encoded, _) = encode_with(value, |size| needs_offset(size, params) > 0)?;
+    Ok(encoded)
+}
+
+/// encoded_len returns the length in bytes of the abi encoding of a
+/// value, without encoding it. `to_vec` writes it as hex, which is twice
+/// as long
+pub fn encoded_len<T: ?Sized + ser::Serialize>(value: &T) -> Result<usize> {
+    len_with(value, false)
+}
+
+/// encoded_len_params returns the length in bytes of the abi encoding
+/// of a value as the list of parameters of a function call, e.g. to size
+/// its calldata, which also has the 4 bytes of the selector
+pub fn encoded_len_params<T: ?Sized + ser::Serialize>(value: &T) -> Result<usize> {
+    len_with(value, true)
+}
+
+fn len_with<T: ?Sized + ser::Serialize>(value: &T, params: bool) -> Result<usize> {
+    let mut sizes = SizeSerializer::default();
+    value.serialize(&mut sizes)?;
+    let size = sizes.sizes.first().copied().unwrap_or_default();
+    Ok(needs_offset(&size, params) + size.len)
+}
+
+pub fn to_writer<W: io::Write, T: ?Sized + ser::Serialize>(mut writer: W, value: &T) -> Result<()> {
+    let encoded = encode(value, false)?;
+    writer.write_all(&encoded).map_err(Error::io)
+}
+
+/// to_writer_streaming writes the encoding of a value to a writer as it
+/// is produced. Words are written in order, so the content of a dynamic
+/// value that comes before the heads after it is buffered until those
+/// are written. Strings and byte arrays longer than 512 bytes are never
+/// buffered nor copied: when they cannot be written yet, a second pass
+/// over the value writes them, so the value is serialized at most three
+/// times including the pass that computes the sizes.
+pub fn to_writer_streaming<W: io::Write, T: ?Sized + ser::Serialize>(
+    writer: W,
+    value: &T,
+) -> Result<()> {
+    let mut sizes = SizeSerializer::default();
+    value.serialize(&mut sizes)?;
+    let size = sizes.sizes.first().copied().unwrap_or_default();
+    let offset = needs_offset(&size, false);
+
+    let mut stream = Stream::new(writer);
+    for second_pass in [false, true] {
+        stream.second_pass = second_pass;
+        let mut ser = WordSerializer::new(&sizes.sizes, offset, stream)?;
+        value.serialize(&mut ser)?;
+        if ser.next != sizes.sizes.len() {
+            return Err(unstable_error());
+        }
+        stream = ser.out;
+        if !stream.deferred {
+            break;
+        }
+    }
+
+    // everything is written once the deferred byte strings are, since
+    // what follows them is flushed right after
+    if stream.written != offset + size.len || !stream.pending.is_empty() {
+        return Err(unstable_error());
+    }
     Ok(())
 }
 
 pub fn to_vec<T: ?Sized + ser::Serialize>(value: &T) -> Result<Vec<u8>> {
-    let mut writer = Vec::with_capacity(128);
-    to_writer(&mut writer, value)?;
-    Ok(writer)
+    encode(value, false)
 }
 
 pub fn to_string<T: ?Sized + ser::Serialize>(value: &T) -> Result<String> {
@@ -904,19 +1301,79 @@ pub fn to_string<T: ?Sized + ser::Serialize>(value: &T) -> Result<String> {
     Ok(string)
 }
 
+/// to_string_params serializes a tuple as the list of parameters of a
+/// function call. Unlike `to_string`, a dynamic tuple is not preceded
+/// by its offset. Values that are not tuples are serialized as the only
+/// parameter, so a single tuple parameter has to be wrapped in a tuple.
+pub fn to_string_params<T: ?Sized + ser::Serialize>(value: &T) -> Result<String> {
+    let vec = encode(value, true)?;
+    let string = unsafe {
+        // We do not emit invalid UTF-8.
+        String::from_utf8_unchecked(vec)
+    };
+    Ok(string)
+}
+
 #[cfg(test)]
 mod tests {
 
-    use super::to_string;
-    use crate::serde_tests;
+    use super::{
+        encoded_len, encoded_len_params, to_string, to_string_params, to_vec, to_writer_streaming,
+        Bytes, Serializer,
+    };
+    use crate::{
+        diff::{diff, diff_params},
+        io, serde_tests,
+    };
+    use alloc::{
+        string::{String, ToString},
+        vec::Vec,
+    };
+    use core::{cell::Cell, fmt::Debug};
     use serde::Serialize;
-    use std::fmt::Debug;
 
     fn test_encode_ok<T: PartialEq + Debug + Serialize>(errors: &[(T, &str)]) {
         for &(ref value, out) in errors {
             let out = out.to_string();
             let s = to_string(value).unwrap();
+            if let Some(difference) = diff(value, &out).unwrap() {
+                panic!("encoding of {:?} differs: {}", value, difference);
+            }
             assert_eq!(s, out);
+
+            // to_vec writes hex, with two digits per byte
+            assert_eq!(
+                encoded_len(value).unwrap(),
+                to_vec(value).unwrap().len() / 2
+            );
+            assert_eq!(encoded_len(value).unwrap(), out.len() / 2);
+        }
+    }
+
+    fn test_encode_params_ok<T: PartialEq + Debug + Serialize>(tests: &[(T, &str)]) {
+        for &(ref value, out) in tests {
+            let s = to_string_params(value).unwrap();
+            if let Some(difference) = diff_params(value, out).unwrap() {
+                panic!("encoding of {:?} differs: {}", value, difference);
+            }
+            assert_eq!(s, out.to_string());
+            assert_eq!(encoded_len_params(value).unwrap(), out.len() / 2);
+        }
+    }
+
+    fn test_encode_serializer_ok<T: PartialEq + Debug + Serialize>(tests: &[(T, &str)]) {
+        for &(ref value, out) in tests {
+            let mut ser = Serializer::new(Vec::new());
+            value.serialize(&mut ser).unwrap();
+            assert_eq!(String::from_utf8(ser.into_inner()).unwrap(), out);
+        }
+    }
+
+    fn test_encode_streaming_ok<T: PartialEq + Debug + Serialize>(tests: &[(T, &str)]) {
+        for &(ref value, out) in tests {
+            let mut vec = Vec::new();
+            to_writer_streaming(&mut vec, value).unwrap();
+            assert_eq!(String::from_utf8(vec).unwrap(), out);
         }
     }
 
@@ -930,6 +1387,11 @@ mod tests {
         test_encode_ok(&serde_tests::test_address()[..]);
     }
 
+    #[test]
+    fn test_write_checksum_address() {
+        test_encode_ok(&serde_tests::test_checksum_address()[..]);
+    }
+
     #[test]
     fn test_write_h256() {
         test_encode_ok(&serde_tests::test_h256()[..]);
@@ -1045,6 +1507,11 @@ mod tests {
         test_encode_ok(&serde_tests::test_complex_struct()[..]);
     }
 
+    #[test]
+    fn test_write_nested_struct() {
+        test_encode_ok(&serde_tests::test_nested_struct()[..]);
+    }
+
     #[test]
     fn test_write_composed_struct() {
         test_encode_ok(&serde_tests::test_composed_struct()[..]);
@@ -1059,4 +1526,106 @@ mod tests {
     fn test_write_reversed_composed_struct() {
         test_encode_ok(&serde_tests::test_reversed_composed_struct()[..]);
     }
+
+    #[test]
+    fn test_write_spec() {
+        test_encode_params_ok(&serde_tests::test_spec_baz()[..]);
+        test_encode_params_ok(&serde_tests::test_spec_bar()[..]);
+        test_encode_params_ok(&serde_tests::test_spec_sam()[..]);
+        test_encode_params_ok(&serde_tests::test_spec_f()[..]);
+        test_encode_params_ok(&serde_tests::test_spec_g()[..]);
+    }
+
+    #[test]
+    fn test_write_serializer() {
+        test_encode_serializer_ok(&serde_tests::test_h256()[..]);
+        test_encode_serializer_ok(&serde_tests::test_i64()[..]);
+        test_encode_serializer_ok(&serde_tests::test_string()[..]);
+        test_encode_serializer_ok(&serde_tests::test_option()[..]);
+        test_encode_serializer_ok(&serde_tests::test_unit()[..]);
+        test_encode_serializer_ok(&serde_tests::test_tuple_mixed()[..]);
+        test_encode_serializer_ok(&serde_tests::test_multiseq()[..]);
+        test_encode_serializer_ok(&serde_tests::test_composed_struct()[..]);
+        test_encode_serializer_ok(&serde_tests::test_reversed_composed_struct()[..]);
+    }
+
+    #[test]
+    fn test_write_streaming() {
+        test_encode_streaming_ok(&serde_tests::test_h256()[..]);
+        test_encode_streaming_ok(&serde_tests::test_i64()[..]);
+        test_encode_streaming_ok(&serde_tests::test_string()[..]);
+        test_encode_streaming_ok(&serde_tests::test_option()[..]);
+        test_encode_streaming_ok(&serde_tests::test_unit()[..]);
+        test_encode_streaming_ok(&serde_tests::test_tuple_mixed()[..]);
+        test_encode_streaming_ok(&serde_tests::test_multiseq()[..]);
+        test_encode_streaming_ok(&serde_tests::test_nested_struct()[..]);
+        test_encode_streaming_ok(&serde_tests::test_composed_struct()[..]);
+        test_encode_streaming_ok(&serde_tests::test_reversed_composed_struct()[..]);
+    }
+
+    /// Writes records the length of the largest write
+    #[derive(Default)]
+    struct Writes {
+        out: Vec<u8>,
+        largest: usize,
+    }
+
+    impl io::Write for Writes {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.largest = self.largest.max(buf.len());
+            self.out.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Counted counts how many times a value is serialized
+    struct Counted<'a, T>(&'a Cell<usize>, T);
+
+    impl<'a, T: Serialize> Serialize for Counted<'a, T> {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.0.set(self.0.get() + 1);
+            self.1.serialize(serializer)
+        }
+    }
+
+    #[test]
+    fn test_write_streaming_passes() {
+        // the content of every inner vector comes before the offsets of
+        // the ones after it, and the long byte strings cannot be buffered
+        let count = Cell::new(0);
+        let data = vec![0xcd; 4096];
+        for n in &[1, 16, 1600] {
+            let value: Vec<Vec<_>> = (0..*n)
+                .map(|i| {
+                    vec![
+                        (Counted(&count, i.to_string()), Bytes(&[1, 2, 3])),
+                        (Counted(&count, "b".to_string()), Bytes(&data)),
+                    ]
+                })
+                .collect();
+
+            let mut vec = Vec::new();
+            count.set(0);
+            to_writer_streaming(&mut vec, &value).unwrap();
+
+            // the sizes, then one pass that writes everything but the
+            // long byte strings, and at most one more for them
+            assert!(count.get() <= 3 * 2 * n);
+            assert_eq!(String::from_utf8(vec).unwrap(), to_string(&value).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_write_streaming_bytes() {
+        let data = vec![0xab; 1 << 20];
+        let value = (7u64, Bytes(&data), "tail".to_string());
+
+        let mut writes = Writes::default();
+        to_writer_streaming(&mut writes, &value).unwrap();
+        assert!(writes.largest <= 1024);
+        assert_eq!(writes.out, to_string(&value).unwrap().into_bytes());
+    }
 }
@@ -1,9 +1,13 @@
+use crate::{address, FixedBytes};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
 use oasis_std::types::{Address, H160, H256, U256};
 use serde::{Deserialize, Serialize};
-use std::vec::Vec;
 
 fn gen_u256(n: u64) -> U256 {
-    let mut v = [0 as u8; 32];
+    let mut v = [0u8; 32];
     v[31] = (n & 0x00ff) as u8;
     v[30] = ((n >> 8) & 0x00ff) as u8;
     v[29] = ((n >> 16) & 0x00ff) as u8;
@@ -16,7 +20,7 @@ fn gen_u256(n: u64) -> U256 {
 }
 
 fn gen_h256(n: u64) -> H256 {
-    let mut v = [0 as u8; 32];
+    let mut v = [0u8; 32];
     v[31] = (n & 0x00ff) as u8;
     v[30] = ((n >> 8) & 0x00ff) as u8;
     v[29] = ((n >> 16) & 0x00ff) as u8;
@@ -29,7 +33,7 @@ fn gen_h256(n: u64) -> H256 {
 }
 
 fn gen_h160(n: u64) -> H160 {
-    let mut v = [0 as u8; 20];
+    let mut v = [0u8; 20];
     v[19] = (n & 0x00ff) as u8;
     v[18] = ((n >> 8) & 0x00ff) as u8;
     v[17] = ((n >> 16) & 0x00ff) as u8;
@@ -53,9 +57,18 @@ pub(crate) struct Complex {
     simple: Simple,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(crate) struct Nested {
+    value: u64,
+    simple: Simple,
+    values: Vec<String>,
+}
+
+type ComposedItem = (String, (H256, [u32; 4]));
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub(crate) struct Composed {
-    field: Vec<Vec<(String, (H256, [u32; 4]))>>,
+    field: Vec<Vec<ComposedItem>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -63,9 +76,11 @@ pub(crate) struct StringComposed {
     field: Vec<Vec<(String, (H256, String))>>,
 }
 
+type ReversedComposedItem = ((H256, [u32; 4]), String);
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub(crate) struct ReversedComposed {
-    field: Vec<Vec<((H256, [u32; 4]), String)>>,
+    field: Vec<Vec<ReversedComposedItem>>,
 }
 
 #[allow(dead_code)]
@@ -96,7 +111,7 @@ pub(crate) fn test_h160() -> Vec<(H160, &'static str)> {
             "00000000000000000000000000000000000000000000000000000000000186a0",
         ),
         (
-            gen_h160(u64::max_value()),
+            gen_h160(u64::MAX),
             "000000000000000000000000000000000000000000000000ffffffffffffffff",
         ),
     ]
@@ -130,12 +145,32 @@ pub(crate) fn test_address() -> Vec<(Address, &'static str)> {
             "00000000000000000000000000000000000000000000000000000000000186a0",
         ),
         (
-            gen_h160(u64::max_value()) as Address,
+            gen_h160(u64::MAX) as Address,
             "000000000000000000000000000000000000000000000000ffffffffffffffff",
         ),
     ]
 }
 
+#[allow(dead_code)]
+pub(crate) fn test_checksum_address() -> Vec<(address::Address, &'static str)> {
+    vec![
+        (
+            address::Address::zero(),
+            "0000000000000000000000000000000000000000000000000000000000000000",
+        ),
+        (
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+                .parse()
+                .unwrap(),
+            "0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+        ),
+        (
+            address::Address::from(gen_h160(1_000).0),
+            "00000000000000000000000000000000000000000000000000000000000003e8",
+        ),
+    ]
+}
+
 #[allow(dead_code)]
 pub(crate) fn test_h256() -> Vec<(H256, &'static str)> {
     vec![
@@ -164,7 +199,7 @@ pub(crate) fn test_h256() -> Vec<(H256, &'static str)> {
             "00000000000000000000000000000000000000000000000000000000000186a0",
         ),
         (
-            gen_h256(u64::max_value()),
+            gen_h256(u64::MAX),
             "000000000000000000000000000000000000000000000000ffffffffffffffff",
         ),
     ]
@@ -198,7 +233,7 @@ pub(crate) fn test_u256() -> Vec<(U256, &'static str)> {
             "00000000000000000000000000000000000000000000000000000000000186a0",
         ),
         (
-            gen_u256(u64::max_value()),
+            gen_u256(u64::MAX),
             "000000000000000000000000000000000000000000000000ffffffffffffffff",
         ),
     ]
@@ -222,23 +257,23 @@ pub(crate) fn test_bool() -> Vec<(bool, &'static str)> {
 pub(crate) fn test_u8() -> Vec<(u8, &'static str)> {
     vec![
         (
-            0x00 as u8,
+            0x00_u8,
             "0000000000000000000000000000000000000000000000000000000000000000",
         ),
         (
-            0x01 as u8,
+            0x01_u8,
             "0000000000000000000000000000000000000000000000000000000000000001",
         ),
         (
-            0x10 as u8,
+            0x10_u8,
             "0000000000000000000000000000000000000000000000000000000000000010",
         ),
         (
-            0x80 as u8,
+            0x80_u8,
             "0000000000000000000000000000000000000000000000000000000000000080",
         ),
         (
-            0xff as u8,
+            0xff_u8,
             "00000000000000000000000000000000000000000000000000000000000000ff",
         ),
     ]
@@ -249,23 +284,23 @@ pub(crate) fn test_u8() -> Vec<(u8, &'static str)> {
 pub(crate) fn test_i8() -> Vec<(i8, &'static str)> {
     vec![
         (
-            0x00 as i8,
+            0x00_i8,
             "0000000000000000000000000000000000000000000000000000000000000000",
         ),
         (
-            0x01 as i8,
+            0x01_i8,
             "0000000000000000000000000000000000000000000000000000000000000001",
         ),
         (
-            0x10 as i8,
+            0x10_i8,
             "0000000000000000000000000000000000000000000000000000000000000010",
         ),
         (
-            0x80 as i8,
+            0x80_i8,
             "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff80",
         ),
         (
-            0xff as i8,
+            0xff_i8,
             "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
         ),
     ]
@@ -275,23 +310,23 @@ pub(crate) fn test_i8() -> Vec<(i8, &'static str)> {
 pub(crate) fn test_u16() -> Vec<(u16, &'static str)> {
     vec![
         (
-            0x0000 as u16,
+            0x0000_u16,
             "0000000000000000000000000000000000000000000000000000000000000000",
         ),
         (
-            0x0100 as u16,
+            0x0100_u16,
             "0000000000000000000000000000000000000000000000000000000000000100",
         ),
         (
-            0x1000 as u16,
+            0x1000_u16,
             "0000000000000000000000000000000000000000000000000000000000001000",
         ),
         (
-            0x8000 as u16,
+            0x8000_u16,
             "0000000000000000000000000000000000000000000000000000000000008000",
         ),
         (
-            0xffff as u16,
+            0xffff_u16,
             "000000000000000000000000000000000000000000000000000000000000ffff",
         ),
     ]
@@ -302,23 +337,23 @@ pub(crate) fn test_u16() -> Vec<(u16, &'static str)> {
 pub(crate) fn test_i16() -> Vec<(i16, &'static str)> {
     vec![
         (
-            0x0000 as i16,
+            0x0000_i16,
             "0000000000000000000000000000000000000000000000000000000000000000",
         ),
         (
-            0x0100 as i16,
+            0x0100_i16,
             "0000000000000000000000000000000000000000000000000000000000000100",
         ),
         (
-            0x1000 as i16,
+            0x1000_i16,
             "0000000000000000000000000000000000000000000000000000000000001000",
         ),
         (
-            0x8000 as i16,
+            0x8000_i16,
             "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff8000",
         ),
         (
-            0xffff as i16,
+            0xffff_i16,
             "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
         ),
     ]
@@ -328,23 +363,23 @@ pub(crate) fn test_i16() -> Vec<(i16, &'static str)> {
 pub(crate) fn test_u32() -> Vec<(u32, &'static str)> {
     vec![
         (
-            0x00000000 as u32,
+            0x00000000_u32,
             "0000000000000000000000000000000000000000000000000000000000000000",
         ),
         (
-            0x01000000 as u32,
+            0x01000000_u32,
             "0000000000000000000000000000000000000000000000000000000001000000",
         ),
         (
-            0x10000000 as u32,
+            0x10000000_u32,
             "0000000000000000000000000000000000000000000000000000000010000000",
         ),
         (
-            0x80000000 as u32,
+            0x80000000_u32,
             "0000000000000000000000000000000000000000000000000000000080000000",
         ),
         (
-            0xffffffff as u32,
+            0xffffffff_u32,
             "00000000000000000000000000000000000000000000000000000000ffffffff",
         ),
     ]
@@ -355,23 +390,23 @@ pub(crate) fn test_u32() -> Vec<(u32, &'static str)> {
 pub(crate) fn test_i32() -> Vec<(i32, &'static str)> {
     vec![
         (
-            0x00000000 as i32,
+            0x00000000_i32,
             "0000000000000000000000000000000000000000000000000000000000000000",
         ),
         (
-            0x01000000 as i32,
+            0x01000000_i32,
             "0000000000000000000000000000000000000000000000000000000001000000",
         ),
         (
-            0x10000000 as i32,
+            0x10000000_i32,
             "0000000000000000000000000000000000000000000000000000000010000000",
         ),
         (
-            0x80000000 as i32,
+            0x80000000_i32,
             "ffffffffffffffffffffffffffffffffffffffffffffffffffffffff80000000",
         ),
         (
-            0xffffffff as i32,
+            0xffffffff_i32,
             "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
         ),
     ]
@@ -381,23 +416,23 @@ pub(crate) fn test_i32() -> Vec<(i32, &'static str)> {
 pub(crate) fn test_u64() -> Vec<(u64, &'static str)> {
     vec![
         (
-            0x0000000000000000 as u64,
+            0x0000000000000000_u64,
             "0000000000000000000000000000000000000000000000000000000000000000",
         ),
         (
-            0x0100000000000000 as u64,
+            0x0100000000000000_u64,
             "0000000000000000000000000000000000000000000000000100000000000000",
         ),
         (
-            0x1000000000000000 as u64,
+            0x1000000000000000_u64,
             "0000000000000000000000000000000000000000000000001000000000000000",
         ),
         (
-            0x8000000000000000 as u64,
+            0x8000000000000000_u64,
             "0000000000000000000000000000000000000000000000008000000000000000",
         ),
         (
-            0xffffffffffffffff as u64,
+            0xffffffffffffffff_u64,
             "000000000000000000000000000000000000000000000000ffffffffffffffff",
         ),
     ]
@@ -408,23 +443,23 @@ pub(crate) fn test_u64() -> Vec<(u64, &'static str)> {
 pub(crate) fn test_i64() -> Vec<(i64, &'static str)> {
     vec![
         (
-            0x0000000000000000 as i64,
+            0x0000000000000000_i64,
             "0000000000000000000000000000000000000000000000000000000000000000",
         ),
         (
-            0x0100000000000000 as i64,
+            0x0100000000000000_i64,
             "0000000000000000000000000000000000000000000000000100000000000000",
         ),
         (
-            0x1000000000000000 as i64,
+            0x1000000000000000_i64,
             "0000000000000000000000000000000000000000000000001000000000000000",
         ),
         (
-            0x8000000000000000 as i64,
+            0x8000000000000000_i64,
             "ffffffffffffffffffffffffffffffffffffffffffffffff8000000000000000",
         ),
         (
-            0xffffffffffffffff as i64,
+            0xffffffffffffffff_i64,
             "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
         ),
     ]
@@ -553,7 +588,7 @@ pub(crate) fn test_seq_int() -> Vec<(Vec<u8>, &'static str)> {
 #[allow(dead_code)]
 pub(crate) fn test_tuple_u8() -> Vec<([u8; 3], &'static str)> {
     vec![(
-        [1 as u8; 3],
+        [1u8; 3],
         "0000000000000000000000000000000000000000000000000000000000000001\
          0000000000000000000000000000000000000000000000000000000000000001\
          0000000000000000000000000000000000000000000000000000000000000001",
@@ -654,6 +689,34 @@ pub(crate) fn test_complex_struct() -> Vec<(Complex, &'static str)> {
     )]
 }
 
+#[allow(dead_code)]
+pub(crate) fn test_nested_struct() -> Vec<(Nested, &'static str)> {
+    vec![(
+        Nested {
+            value: 7,
+            simple: Simple {
+                value1: "a".to_string(),
+                value2: "b".to_string(),
+            },
+            values: vec!["c".to_string()],
+        },
+        "0000000000000000000000000000000000000000000000000000000000000020\
+         0000000000000000000000000000000000000000000000000000000000000007\
+         0000000000000000000000000000000000000000000000000000000000000060\
+         0000000000000000000000000000000000000000000000000000000000000120\
+         0000000000000000000000000000000000000000000000000000000000000040\
+         0000000000000000000000000000000000000000000000000000000000000080\
+         0000000000000000000000000000000000000000000000000000000000000001\
+         6100000000000000000000000000000000000000000000000000000000000000\
+         0000000000000000000000000000000000000000000000000000000000000001\
+         6200000000000000000000000000000000000000000000000000000000000000\
+         0000000000000000000000000000000000000000000000000000000000000001\
+         0000000000000000000000000000000000000000000000000000000000000020\
+         0000000000000000000000000000000000000000000000000000000000000001\
+         6300000000000000000000000000000000000000000000000000000000000000",
+    )]
+}
+
 #[allow(dead_code)]
 pub(crate) fn test_composed_struct() -> Vec<(Composed, &'static str)> {
     let s = "string".to_string();
@@ -735,3 +798,175 @@ pub(crate) fn test_reversed_composed_struct() -> Vec<(ReversedComposed, &'static
          737472696e670000000000000000000000000000000000000000000000000000",
     )]
 }
+
+// The following vectors are the examples of the contract ABI specification
+// in the Solidity documentation. They are the encodings of the parameters of
+// a function call, without the function selector.
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(crate) struct Sam {
+    #[serde(with = "crate::hex_bytes")]
+    name: Vec<u8>,
+    flag: bool,
+    values: Vec<U256>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(crate) struct F {
+    value: U256,
+    values: Vec<u32>,
+    fixed: FixedBytes<10>,
+    #[serde(with = "crate::hex_bytes")]
+    data: Vec<u8>,
+}
+
+/// test_spec_baz is `baz(uint32,bool)` with `69` and `true`
+#[allow(dead_code)]
+pub(crate) fn test_spec_baz() -> Vec<((u32, bool), &'static str)> {
+    vec![(
+        (69, true),
+        "0000000000000000000000000000000000000000000000000000000000000045\
+         0000000000000000000000000000000000000000000000000000000000000001",
+    )]
+}
+
+/// test_spec_bar is `bar(bytes3[2])` with `["abc", "def"]`
+#[allow(dead_code)]
+pub(crate) fn test_spec_bar() -> Vec<(([FixedBytes<3>; 2],), &'static str)> {
+    vec![(
+        ([FixedBytes(*b"abc"), FixedBytes(*b"def")],),
+        "6162630000000000000000000000000000000000000000000000000000000000\
+         6465660000000000000000000000000000000000000000000000000000000000",
+    )]
+}
+
+/// test_spec_sam is `sam(bytes,bool,uint256[])` with `"dave"`, `true`
+/// and `[1, 2, 3]`
+#[allow(dead_code)]
+pub(crate) fn test_spec_sam() -> Vec<(Sam, &'static str)> {
+    vec![(
+        Sam {
+            name: b"dave".to_vec(),
+            flag: true,
+            values: vec![gen_u256(1), gen_u256(2), gen_u256(3)],
+        },
+        "0000000000000000000000000000000000000000000000000000000000000060\
+         0000000000000000000000000000000000000000000000000000000000000001\
+         00000000000000000000000000000000000000000000000000000000000000a0\
+         0000000000000000000000000000000000000000000000000000000000000004\
+         6461766500000000000000000000000000000000000000000000000000000000\
+         0000000000000000000000000000000000000000000000000000000000000003\
+         0000000000000000000000000000000000000000000000000000000000000001\
+         0000000000000000000000000000000000000000000000000000000000000002\
+         0000000000000000000000000000000000000000000000000000000000000003",
+    )]
+}
+
+/// test_spec_f is `f(uint256,uint32[],bytes10,bytes)` with `0x123`,
+/// `[0x456, 0x789]`, `"1234567890"` and `"Hello, world!"`
+#[allow(dead_code)]
+pub(crate) fn test_spec_f() -> Vec<(F, &'static str)> {
+    vec![(
+        F {
+            value: gen_u256(0x123),
+            values: vec![0x456, 0x789],
+            fixed: FixedBytes(*b"1234567890"),
+            data: b"Hello, world!".to_vec(),
+        },
+        "0000000000000000000000000000000000000000000000000000000000000123\
+         0000000000000000000000000000000000000000000000000000000000000080\
+         3132333435363738393000000000000000000000000000000000000000000000\
+         00000000000000000000000000000000000000000000000000000000000000e0\
+         0000000000000000000000000000000000000000000000000000000000000002\
+         0000000000000000000000000000000000000000000000000000000000000456\
+         0000000000000000000000000000000000000000000000000000000000000789\
+         000000000000000000000000000000000000000000000000000000000000000d\
+         48656c6c6f2c20776f726c642100000000000000000000000000000000000000",
+    )]
+}
+
+type G = (Vec<Vec<U256>>, Vec<String>);
+
+/// test_spec_g is `g(uint256[][],string[])` with `[[1, 2], [3]]` and
+/// `["one", "two", "three"]`
+#[allow(dead_code)]
+pub(crate) fn test_spec_g() -> Vec<(G, &'static str)> {
+    vec![(
+        (
+            vec![vec![gen_u256(1), gen_u256(2)], vec![gen_u256(3)]],
+            vec!["one".to_string(), "two".to_string(), "three".to_string()],
+        ),
+        "0000000000000000000000000000000000000000000000000000000000000040\
+         0000000000000000000000000000000000000000000000000000000000000140\
+         0000000000000000000000000000000000000000000000000000000000000002\
+         0000000000000000000000000000000000000000000000000000000000000040\
+         00000000000000000000000000000000000000000000000000000000000000a0\
+         0000000000000000000000000000000000000000000000000000000000000002\
+         0000000000000000000000000000000000000000000000000000000000000001\
+         0000000000000000000000000000000000000000000000000000000000000002\
+         0000000000000000000000000000000000000000000000000000000000000001\
+         0000000000000000000000000000000000000000000000000000000000000003\
+         0000000000000000000000000000000000000000000000000000000000000003\
+         0000000000000000000000000000000000000000000000000000000000000060\
+         00000000000000000000000000000000000000000000000000000000000000a0\
+         00000000000000000000000000000000000000000000000000000000000000e0\
+         0000000000000000000000000000000000000000000000000000000000000003\
+         6f6e650000000000000000000000000000000000000000000000000000000000\
+         0000000000000000000000000000000000000000000000000000000000000003\
+         74776f0000000000000000000000000000000000000000000000000000000000\
+         0000000000000000000000000000000000000000000000000000000000000005\
+         7468726565000000000000000000000000000000000000000000000000000000",
+    )]
+}
+
+macro_rules! encodings {
+    ( $($test:ident),+ ) => {{
+        let mut encodings = Vec::new();
+        $(
+            encodings.extend($test().into_iter().map(|(_, s)| s));
+        )*
+        encodings
+    }}
+}
+
+/// test_encodings returns the encodings of all the test vectors
+#[allow(dead_code)]
+pub(crate) fn test_encodings() -> Vec<&'static str> {
+    encodings!(
+        test_h160,
+        test_address,
+        test_checksum_address,
+        test_h256,
+        test_u256,
+        test_bool,
+        test_u8,
+        test_i8,
+        test_u16,
+        test_i16,
+        test_u32,
+        test_i32,
+        test_u64,
+        test_i64,
+        test_char,
+        test_string,
+        test_option,
+        test_unit,
+        test_tuple_mixed,
+        test_tuple_string,
+        test_seq_int,
+        test_tuple_u8,
+        test_str_seq,
+        test_multiseq,
+        test_simple_struct,
+        test_complex_struct,
+        test_nested_struct,
+        test_composed_struct,
+        test_string_composed_struct,
+        test_reversed_composed_struct,
+        test_spec_baz,
+        test_spec_bar,
+        test_spec_sam,
+        test_spec_f,
+        test_spec_g
+    )
+}
@@ -0,0 +1,142 @@
+//! Helpers shared by the values of `abi_type` and the conversions from and
+//! to the token types of other eth abi libraries, which describe the same
+//! types with different values.
+
+use serde::{de, ser::Serialize, ser::Serializer};
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::{
+    de::{DYNAMIC_TUPLE, STATIC_TUPLE},
+    FixedBytes,
+};
+
+fn overflow_error<E: de::Error>() -> E {
+    E::custom("decoded integer does not fit in integer of specified size")
+}
+
+/// int_len returns the length in bytes of an integer of `bits`
+fn int_len<E: de::Error>(bits: usize) -> Result<usize, E> {
+    if bits == 0 || bits > 256 || !bits.is_multiple_of(8) {
+        return Err(E::custom(format!("invalid integer size {}", bits)));
+    }
+    Ok(bits / 8)
+}
+
+/// uint_word checks that a word is an unsigned integer of `bits`
+pub(crate) fn uint_word<E: de::Error>(word: FixedBytes<32>, bits: usize) -> Result<[u8; 32], E> {
+    let len = int_len(bits)?;
+    if word.0[..32 - len].iter().any(|b| *b != 0) {
+        return Err(overflow_error());
+    }
+    Ok(word.0)
+}
+
+/// int_word checks that a word is a signed integer of `bits`, i.e. that
+/// it is sign extended from them
+pub(crate) fn int_word<E: de::Error>(word: FixedBytes<32>, bits: usize) -> Result<[u8; 32], E> {
+    let len = int_len(bits)?;
+    let sign = if word.0[32 - len] & 0x80 != 0 {
+        0xff
+    } else {
+        0
+    };
+    if word.0[..32 - len].iter().any(|b| *b != sign) {
+        return Err(overflow_error());
+    }
+    Ok(word.0)
+}
+
+/// fixed_bytes_word checks that a word is a `bytesN` of `len` bytes
+pub(crate) fn fixed_bytes_word<E: de::Error>(
+    word: FixedBytes<32>,
+    len: usize,
+) -> Result<[u8; 32], E> {
+    if len == 0 || len > 32 {
+        return Err(E::custom("fixed bytes must be between 1 and 32 bytes long"));
+    }
+    if word.0[len..].iter().any(|b| *b != 0) {
+        return Err(E::custom("fixed bytes are not padded with zeros"));
+    }
+    Ok(word.0)
+}
+
+/// serialize_word serializes a word as it is
+pub(crate) fn serialize_word<S: Serializer>(
+    word: [u8; 32],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    FixedBytes(word).serialize(serializer)
+}
+
+/// serialize_fixed_bytes serializes a `bytesN`, padded on the right
+pub(crate) fn serialize_fixed_bytes<S: Serializer>(
+    bytes: &[u8],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if bytes.is_empty() || bytes.len() > 32 {
+        return Err(serde::ser::Error::custom(
+            "fixed bytes must be between 1 and 32 bytes long",
+        ));
+    }
+
+    let mut word = [0u8; 32];
+    word[..bytes.len()].copy_from_slice(bytes);
+    serialize_word(word, serializer)
+}
+
+/// deserialize_tuple deserializes a tuple of `len` values and tells the
+/// deserializer whether it is dynamic, which the types know
+pub(crate) fn deserialize_tuple<'de, D, V>(
+    deserializer: D,
+    len: usize,
+    is_dynamic: bool,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: de::Deserializer<'de>,
+    V: de::Visitor<'de>,
+{
+    let name = if is_dynamic {
+        DYNAMIC_TUPLE
+    } else {
+        STATIC_TUPLE
+    };
+    deserializer.deserialize_tuple_struct(name, len, visitor)
+}
+
+/// Elements visits a dynamically sized array of `element`, or a tuple
+/// of `seeds` if there is no element
+pub(crate) struct Elements<S> {
+    pub(crate) seeds: Vec<S>,
+    pub(crate) element: Option<S>,
+}
+
+impl<'de, S: de::DeserializeSeed<'de> + Copy> de::Visitor<'de> for Elements<S> {
+    type Value = Vec<S::Value>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence of values")
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = Vec::new();
+        match self.element {
+            Some(seed) => {
+                while let Some(value) = seq.next_element_seed(seed)? {
+                    values.push(value);
+                }
+            }
+            None => {
+                for (index, seed) in self.seeds.iter().enumerate() {
+                    let value = seq
+                        .next_element_seed(*seed)?
+                        .ok_or_else(|| de::Error::invalid_length(index, &self))?;
+                    values.push(value);
+                }
+            }
+        }
+        Ok(values)
+    }
+}